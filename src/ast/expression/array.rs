@@ -1,6 +1,7 @@
 use crate::ast::expression::Expression;
 use crate::ast::NodeInterface;
 use crate::error::Error;
+use crate::token::span::Span;
 use crate::token::Token;
 use std::fmt::{Display, Formatter};
 
@@ -18,10 +19,22 @@ impl ArrayLiteral {
         }
     }
 
+    pub fn span(&self) -> Span {
+        self.elements
+            .iter()
+            .fold(self.token.span(), |span, element| {
+                span.merge(element.span())
+            })
+    }
+
     pub fn elements(&self) -> &[Expression] {
         &self.elements
     }
 
+    pub fn elements_mut(&mut self) -> &mut Vec<Expression> {
+        &mut self.elements
+    }
+
     pub fn update_elements(&mut self, elements: Vec<Expression>) {
         self.elements = elements;
     }
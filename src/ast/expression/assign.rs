@@ -0,0 +1,107 @@
+use crate::ast::expression::integer::IntegerLiteral;
+use crate::ast::expression::Expression;
+use crate::ast::NodeInterface;
+use crate::error::Error;
+use crate::token::span::Span;
+use crate::token::Token;
+use std::fmt::{Display, Formatter};
+
+/// 赋值表达式：`x = v`、`arr[i] = v`、`h["k"] = v`
+/// 目标只能是标识符或者以标识符为根的索引表达式
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Assign {
+    token: Token,
+    target: Box<Expression>,
+    value: Box<Expression>,
+}
+
+impl Assign {
+    pub fn new(token: Token, target: Expression) -> Self {
+        Self {
+            token,
+            target: Box::new(target),
+            ..Default::default()
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        self.target.span().merge(self.value.span())
+    }
+
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+
+    pub fn target(&self) -> &Expression {
+        &self.target
+    }
+
+    pub fn value(&self) -> &Expression {
+        &self.value
+    }
+
+    pub fn target_mut(&mut self) -> &mut Expression {
+        &mut self.target
+    }
+
+    pub fn value_mut(&mut self) -> &mut Expression {
+        &mut self.value
+    }
+
+    pub fn update_value(&mut self, value: Expression) {
+        *self.value = value;
+    }
+
+    /// 判断表达式能否作为赋值目标
+    pub fn is_assignable(target: &Expression) -> bool {
+        match target {
+            Expression::Identifier(_) => true,
+            Expression::Index(value) => Self::is_assignable(value.left()),
+            _ => false,
+        }
+    }
+}
+
+impl Default for Assign {
+    fn default() -> Self {
+        Self {
+            token: Token::default(),
+            target: Box::new(IntegerLiteral::default().into()),
+            value: Box::new(IntegerLiteral::default().into()),
+        }
+    }
+}
+
+impl Display for Assign {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({} = {})", self.target, self.value)
+    }
+}
+
+impl NodeInterface for Assign {
+    fn token_literal(&self) -> &str {
+        self.token.literal()
+    }
+}
+
+impl TryFrom<Expression> for Assign {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Expression) -> Result<Self, Self::Error> {
+        match value {
+            Expression::Assign(value) => Ok(value),
+            unknow => Err(Error::UnknownExpression(unknow.to_string()).into()),
+        }
+    }
+}
+
+impl TryFrom<&Expression> for Assign {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &Expression) -> Result<Self, Self::Error> {
+        match value {
+            Expression::Assign(value) => Ok(value.clone()),
+            unknow => Err(Error::UnknownExpression(unknow.to_string()).into()),
+        }
+    }
+}
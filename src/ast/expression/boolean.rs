@@ -1,6 +1,7 @@
 use crate::ast::expression::Expression;
 use crate::ast::NodeInterface;
 use crate::error::Error;
+use crate::token::span::Span;
 use crate::token::Token;
 use std::fmt::{Display, Formatter};
 
@@ -19,6 +20,10 @@ impl Boolean {
         self.value
     }
 
+    pub fn span(&self) -> Span {
+        self.token.span()
+    }
+
     pub fn token(&self) -> &Token {
         &self.token
     }
@@ -51,7 +56,7 @@ impl TryFrom<Expression> for Boolean {
                 value: value.value.parse()?,
             }),
             unknow => {
-                tracing::error!("[try_from] Expression is ({unknow})");
+                tracing::debug!("[try_from] Expression is ({unknow})");
                 Err(Error::UnknownExpression(unknow.to_string()).into())
             }
         }
@@ -1,6 +1,7 @@
 use crate::ast::expression::Expression;
 use crate::ast::NodeInterface;
 use crate::error::Error;
+use crate::token::span::Span;
 use crate::token::Token;
 use std::fmt::{Display, Formatter};
 
@@ -20,6 +21,13 @@ impl Call {
         }
     }
 
+    pub fn span(&self) -> Span {
+        self.arguments.iter().fold(
+            self.function.span().merge(self.token.span()),
+            |span, argument| span.merge(argument.span()),
+        )
+    }
+
     pub fn token(&self) -> &Token {
         &self.token
     }
@@ -32,6 +40,14 @@ impl Call {
         &self.arguments
     }
 
+    pub fn function_mut(&mut self) -> &mut Expression {
+        &mut self.function
+    }
+
+    pub fn arguments_mut(&mut self) -> &mut Vec<Expression> {
+        &mut self.arguments
+    }
+
     pub fn update_arguments(&mut self, arguments: Vec<Expression>) {
         self.arguments = arguments;
     }
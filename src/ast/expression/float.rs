@@ -0,0 +1,72 @@
+use crate::ast::expression::Expression;
+use crate::ast::NodeInterface;
+use crate::error::Error;
+use crate::object::float::Float;
+use crate::token::span::Span;
+use crate::token::Token;
+use std::fmt::{Debug, Display, Formatter};
+
+#[derive(Debug, Default, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct FloatLiteral {
+    token: Token,
+    value: Float,
+}
+
+impl FloatLiteral {
+    pub fn new(token: Token) -> Self {
+        Self {
+            token,
+            value: Default::default(),
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        self.token.span()
+    }
+
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+
+    pub fn value(&self) -> Float {
+        self.value
+    }
+
+    pub fn update_value(&mut self, value: Float) {
+        self.value = value;
+    }
+}
+
+impl Display for FloatLiteral {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.token.literal())
+    }
+}
+
+impl NodeInterface for FloatLiteral {
+    fn token_literal(&self) -> &str {
+        "float_literal"
+    }
+}
+
+impl TryFrom<Expression> for FloatLiteral {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Expression) -> Result<Self, Self::Error> {
+        match value {
+            Expression::FloatLiteral(value) => Ok(value),
+            unknow => Err(Error::UnknownExpression(unknow.to_string()).into()),
+        }
+    }
+}
+
+impl TryFrom<&Expression> for FloatLiteral {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &Expression) -> Result<Self, Self::Error> {
+        match value {
+            Expression::FloatLiteral(value) => Ok(value.clone()),
+            unknow => Err(Error::UnknownExpression(unknow.to_string()).into()),
+        }
+    }
+}
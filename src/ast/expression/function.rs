@@ -2,6 +2,7 @@ use crate::ast::expression::Expression;
 use crate::ast::statement::block::BlockStatement;
 use crate::ast::{Identifier, NodeInterface};
 use crate::error::Error;
+use crate::token::span::Span;
 use crate::token::Token;
 use std::fmt::{Display, Formatter};
 
@@ -21,10 +22,18 @@ impl FunctionLiteral {
         }
     }
 
+    pub fn span(&self) -> Span {
+        self.token.span().merge(self.body.span())
+    }
+
     pub fn body(&self) -> &BlockStatement {
         &self.body
     }
 
+    pub fn body_mut(&mut self) -> &mut BlockStatement {
+        &mut self.body
+    }
+
     pub fn update_body(&mut self, body: BlockStatement) {
         self.body = body;
     }
@@ -33,6 +42,10 @@ impl FunctionLiteral {
         &self.parameters
     }
 
+    pub fn parameters_mut(&mut self) -> &mut Vec<Identifier> {
+        &mut self.parameters
+    }
+
     pub fn update_parameters(&mut self, parameters: Vec<Identifier>) {
         self.parameters = parameters;
     }
@@ -1,6 +1,7 @@
 use crate::ast::expression::Expression;
 use crate::ast::NodeInterface;
 use crate::error::Error;
+use crate::token::span::Span;
 use crate::token::Token;
 use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
@@ -20,6 +21,14 @@ impl HashLiteral {
         }
     }
 
+    pub fn span(&self) -> Span {
+        self.pair
+            .iter()
+            .fold(self.token.span(), |span, (key, value)| {
+                span.merge(key.span()).merge(value.span())
+            })
+    }
+
     pub fn token(&self) -> &Token {
         &self.token
     }
@@ -3,6 +3,7 @@ use crate::ast::expression::Expression;
 use crate::ast::statement::block::BlockStatement;
 use crate::ast::NodeInterface;
 use crate::error::Error;
+use crate::token::span::Span;
 use crate::token::Token;
 use std::fmt::{Display, Formatter};
 
@@ -22,6 +23,17 @@ impl If {
         }
     }
 
+    pub fn span(&self) -> Span {
+        let mut span = self.token.span().merge(self.condition.span());
+        if let Some(consequence) = &self.consequence {
+            span = span.merge(consequence.span());
+        }
+        if let Some(alternative) = &self.alternative {
+            span = span.merge(alternative.span());
+        }
+        span
+    }
+
     pub fn alternative(&self) -> &Option<BlockStatement> {
         &self.alternative
     }
@@ -34,6 +46,18 @@ impl If {
         &self.condition
     }
 
+    pub fn condition_mut(&mut self) -> &mut Expression {
+        &mut self.condition
+    }
+
+    pub fn consequence_mut(&mut self) -> Option<&mut BlockStatement> {
+        self.consequence.as_mut()
+    }
+
+    pub fn alternative_mut(&mut self) -> Option<&mut BlockStatement> {
+        self.alternative.as_mut()
+    }
+
     pub fn update_alternative(&mut self, alternative: BlockStatement) {
         self.alternative = Some(alternative);
     }
@@ -43,7 +67,7 @@ impl If {
     }
 
     pub fn update_expression(&mut self, expression: Expression) {
-        self.condition = Box::new(expression);
+        *self.condition = expression;
     }
 }
 
@@ -63,12 +87,12 @@ impl Display for If {
         write!(f, "if ")?;
         write!(f, "{}", self.condition)?;
         write!(f, " ")?;
-        if self.consequence.is_some() {
-            write!(f, "{}", self.consequence.as_ref().unwrap())?;
+        if let Some(consequence) = &self.consequence {
+            write!(f, "{consequence}")?;
         }
-        if self.alternative.is_some() {
+        if let Some(alternative) = &self.alternative {
             write!(f, "else ")?;
-            write!(f, "{}", self.alternative.as_ref().unwrap())?;
+            write!(f, "{alternative}")?;
         }
         Ok(())
     }
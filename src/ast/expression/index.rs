@@ -2,6 +2,7 @@ use crate::ast::expression::Expression;
 use crate::ast::Identifier;
 use crate::ast::NodeInterface;
 use crate::error::Error;
+use crate::token::span::Span;
 use crate::token::Token;
 use std::fmt::{Display, Formatter};
 
@@ -21,6 +22,13 @@ impl Index {
         }
     }
 
+    pub fn span(&self) -> Span {
+        self.left
+            .span()
+            .merge(self.token.span())
+            .merge(self.index.span())
+    }
+
     pub fn left(&self) -> &Expression {
         &self.left
     }
@@ -29,8 +37,20 @@ impl Index {
         &self.index
     }
 
+    pub fn left_mut(&mut self) -> &mut Expression {
+        &mut self.left
+    }
+
+    pub fn index_mut(&mut self) -> &mut Expression {
+        &mut self.index
+    }
+
+    pub fn update_left(&mut self, left: Expression) {
+        *self.left = left;
+    }
+
     pub fn update_index(&mut self, index: Expression) {
-        self.index = Box::new(index);
+        *self.index = index;
     }
 }
 
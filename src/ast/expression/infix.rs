@@ -3,6 +3,7 @@ use crate::ast::expression::Expression;
 use crate::ast::statement::expression::ExpressionStatement;
 use crate::ast::NodeInterface;
 use crate::error::Error;
+use crate::token::span::Span;
 use crate::token::Token;
 use std::fmt::{Display, Formatter};
 
@@ -24,6 +25,15 @@ impl Infix {
         }
     }
 
+    pub fn span(&self) -> Span {
+        self.left.span().merge(self.right.span())
+    }
+
+    /// 运算符所在的位置
+    pub fn operator_span(&self) -> Span {
+        self.token.span()
+    }
+
     pub fn left(&self) -> &Expression {
         &self.left
     }
@@ -36,8 +46,20 @@ impl Infix {
         &self.right
     }
 
+    pub fn left_mut(&mut self) -> &mut Expression {
+        &mut self.left
+    }
+
+    pub fn right_mut(&mut self) -> &mut Expression {
+        &mut self.right
+    }
+
+    pub fn update_left(&mut self, left: Expression) {
+        *self.left = left;
+    }
+
     pub fn update_expression(&mut self, right: Expression) {
-        self.right = Box::new(right);
+        *self.right = right;
     }
 }
 
@@ -2,13 +2,15 @@ use crate::ast::expression::Expression;
 use crate::ast::statement::expression::ExpressionStatement;
 use crate::ast::{Identifier, NodeInterface};
 use crate::error::Error;
+use crate::object::integer::Integer;
+use crate::token::span::Span;
 use crate::token::Token;
 use std::fmt::{Debug, Display, Formatter};
 
 #[derive(Debug, Default, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 pub struct IntegerLiteral {
     token: Token,
-    value: isize,
+    value: Integer,
 }
 
 impl IntegerLiteral {
@@ -19,15 +21,19 @@ impl IntegerLiteral {
         }
     }
 
+    pub fn span(&self) -> Span {
+        self.token.span()
+    }
+
     pub fn token(&self) -> &Token {
         &self.token
     }
 
-    pub fn value(&self) -> isize {
-        self.value
+    pub fn value(&self) -> Integer {
+        self.value.clone()
     }
 
-    pub fn update_value(&mut self, value: isize) {
+    pub fn update_value(&mut self, value: Integer) {
         self.value = value;
     }
 }
@@ -49,7 +55,9 @@ impl TryFrom<ExpressionStatement> for IntegerLiteral {
 
     fn try_from(expression_statement: ExpressionStatement) -> Result<Self, Self::Error> {
         let identifier = Identifier::try_from(expression_statement.expression())?;
-        let value = identifier.value.parse::<isize>()?;
+        let value = Integer::parse(&identifier.value).ok_or_else(|| {
+            Error::IntegerLiteralOutOfRange(identifier.value.clone(), identifier.span())
+        })?;
 
         Ok(Self {
             token: expression_statement.token().clone(),
@@ -69,8 +77,10 @@ impl TryFrom<Expression> for IntegerLiteral {
                 unknow => Err(Error::UnknownExpression(unknow.to_string()).into()),
             },
             Expression::Identifier(value) => Ok(IntegerLiteral {
-                token: value.token.clone(),
-                value: value.value.parse()?,
+                value: Integer::parse(&value.value).ok_or_else(|| {
+                    Error::IntegerLiteralOutOfRange(value.value.clone(), value.span())
+                })?,
+                token: value.token,
             }),
             unknow => Err(Error::UnknownExpression(unknow.to_string()).into()),
         }
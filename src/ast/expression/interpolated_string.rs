@@ -0,0 +1,92 @@
+use crate::ast::expression::string::write_escaped;
+use crate::ast::expression::Expression;
+use crate::ast::NodeInterface;
+use crate::error::Error;
+use crate::token::span::Span;
+use crate::token::Token;
+use std::fmt::{Display, Formatter};
+
+/// 插值字符串 `"hello ${name}!"`。
+///
+/// `strings` 是插值表达式之间的文本，总是比 `expressions` 多一个：
+/// 上面的例子中 `strings` 为 `["hello ", "!"]`，`expressions` 为 `[name]`。
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct InterpolatedString {
+    /// 第一个片段，也就是 STRINGSTART
+    token: Token,
+    strings: Vec<String>,
+    expressions: Vec<Expression>,
+    /// 最后一个片段的位置，和 token 一起确定整个字符串的范围
+    end: Span,
+}
+
+impl InterpolatedString {
+    pub fn new(token: Token) -> Self {
+        Self {
+            strings: vec![token.literal().to_string()],
+            end: token.span(),
+            token,
+            expressions: vec![],
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        self.token.span().merge(self.end)
+    }
+
+    pub fn strings(&self) -> &[String] {
+        &self.strings
+    }
+
+    pub fn expressions(&self) -> &[Expression] {
+        &self.expressions
+    }
+
+    pub fn expressions_mut(&mut self) -> &mut Vec<Expression> {
+        &mut self.expressions
+    }
+
+    /// 追加一个插值表达式以及它后面的文本片段
+    pub fn push(&mut self, expression: Expression, segment: &Token) {
+        self.expressions.push(expression);
+        self.strings.push(segment.literal().to_string());
+        self.end = segment.span();
+    }
+}
+
+impl Display for InterpolatedString {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"")?;
+        write_escaped(f, &self.strings[0])?;
+        for (expression, string) in self.expressions.iter().zip(&self.strings[1..]) {
+            write!(f, "${{{expression}}}")?;
+            write_escaped(f, string)?;
+        }
+        write!(f, "\"")
+    }
+}
+
+impl NodeInterface for InterpolatedString {
+    fn token_literal(&self) -> &str {
+        self.token.literal()
+    }
+}
+
+impl TryFrom<Expression> for InterpolatedString {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Expression) -> Result<Self, Self::Error> {
+        match value {
+            Expression::InterpolatedString(value) => Ok(value),
+            unknow => Err(Error::UnknownExpression(unknow.to_string()).into()),
+        }
+    }
+}
+
+impl TryFrom<&Expression> for InterpolatedString {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &Expression) -> Result<Self, Self::Error> {
+        InterpolatedString::try_from(value.clone())
+    }
+}
@@ -1,28 +1,39 @@
 use crate::ast::expression::array::ArrayLiteral;
+use crate::ast::expression::assign::Assign;
 use crate::ast::expression::boolean::Boolean;
 use crate::ast::expression::call::Call;
+use crate::ast::expression::float::FloatLiteral;
 use crate::ast::expression::function::FunctionLiteral;
 use crate::ast::expression::hash::HashLiteral;
 use crate::ast::expression::if_expression::If;
 use crate::ast::expression::index::Index;
 use crate::ast::expression::infix::Infix;
 use crate::ast::expression::integer::IntegerLiteral;
+use crate::ast::expression::interpolated_string::InterpolatedString;
+use crate::ast::expression::macro_literal::MacroLiteral;
 use crate::ast::expression::prefix::Prefix;
 use crate::ast::expression::string::StringLiteral;
 use crate::ast::Identifier;
+use crate::ast::Node;
 use crate::ast::NodeInterface;
+use crate::error::Error;
+use crate::token::span::Span;
 use derive_more::From;
 use std::fmt::{Display, Formatter};
 
 pub mod array;
+pub mod assign;
 pub mod boolean;
 pub mod call;
+pub mod float;
 pub mod function;
 pub mod hash;
 pub mod if_expression;
 pub mod index;
 pub mod infix;
 pub mod integer;
+pub mod interpolated_string;
+pub mod macro_literal;
 pub mod prefix;
 pub mod string;
 
@@ -40,6 +51,39 @@ pub enum Expression {
     ArrayLiteral(ArrayLiteral),
     Index(Index),
     HashLiteral(HashLiteral),
+    MacroLiteral(MacroLiteral),
+    Assign(Assign),
+    FloatLiteral(FloatLiteral),
+    InterpolatedString(InterpolatedString),
+}
+
+impl Default for Expression {
+    fn default() -> Self {
+        Self::IntegerLiteral(IntegerLiteral::default())
+    }
+}
+
+impl Expression {
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Prefix(value) => value.span(),
+            Self::Infix(value) => value.span(),
+            Self::IntegerLiteral(value) => value.span(),
+            Self::Identifier(value) => value.span(),
+            Self::Boolean(value) => value.span(),
+            Self::If(value) => value.span(),
+            Self::FunctionLiteral(value) => value.span(),
+            Self::Call(value) => value.span(),
+            Self::StringLiteral(value) => value.span(),
+            Self::ArrayLiteral(value) => value.span(),
+            Self::Index(value) => value.span(),
+            Self::HashLiteral(value) => value.span(),
+            Self::MacroLiteral(value) => value.span(),
+            Self::InterpolatedString(value) => value.span(),
+            Self::FloatLiteral(value) => value.span(),
+            Self::Assign(value) => value.span(),
+        }
+    }
 }
 
 impl Display for Expression {
@@ -57,6 +101,21 @@ impl Display for Expression {
             Self::ArrayLiteral(value) => write!(f, "{value}"),
             Self::Index(value) => write!(f, "{value}"),
             Self::HashLiteral(value) => write!(f, "{value}"),
+            Self::MacroLiteral(value) => write!(f, "{value}"),
+            Self::InterpolatedString(value) => write!(f, "{value}"),
+            Self::FloatLiteral(value) => write!(f, "{value}"),
+            Self::Assign(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl TryFrom<Node> for Expression {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Node) -> Result<Self, Self::Error> {
+        match value {
+            Node::Expression(value) => Ok(value),
+            _ => Err(Error::DownCastRefExpressionError.into()),
         }
     }
 }
@@ -76,6 +135,10 @@ impl NodeInterface for Expression {
             Self::ArrayLiteral(value) => value.token_literal(),
             Self::Index(value) => value.token_literal(),
             Self::HashLiteral(value) => value.token_literal(),
+            Self::MacroLiteral(value) => value.token_literal(),
+            Self::InterpolatedString(value) => value.token_literal(),
+            Self::FloatLiteral(value) => value.token_literal(),
+            Self::Assign(value) => value.token_literal(),
         }
     }
 }
@@ -3,6 +3,7 @@ use crate::ast::expression::Expression;
 use crate::ast::statement::expression::ExpressionStatement;
 use crate::ast::NodeInterface;
 use crate::error::Error;
+use crate::token::span::Span;
 use crate::token::Token;
 use std::fmt::{Display, Formatter};
 
@@ -22,6 +23,10 @@ impl Prefix {
         }
     }
 
+    pub fn span(&self) -> Span {
+        self.token.span().merge(self.right.span())
+    }
+
     pub fn operator(&self) -> &str {
         self.operator.as_str()
     }
@@ -30,8 +35,12 @@ impl Prefix {
         &self.right
     }
 
+    pub fn right_mut(&mut self) -> &mut Expression {
+        &mut self.right
+    }
+
     pub fn update_expression(&mut self, expression: Expression) {
-        self.right = Box::new(expression);
+        *self.right = expression;
     }
 }
 
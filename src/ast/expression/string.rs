@@ -1,6 +1,7 @@
 use crate::ast::expression::Expression;
 use crate::ast::NodeInterface;
 use crate::error::Error;
+use crate::token::span::Span;
 use crate::token::Token;
 use std::fmt::{Display, Formatter};
 
@@ -15,15 +16,41 @@ impl StringLiteral {
         Self { token, value }
     }
 
+    pub fn span(&self) -> Span {
+        self.token.span()
+    }
+
     pub fn value(&self) -> &str {
         self.value.as_str()
     }
 }
 
+/// 输出带引号的字面量，并把需要转义的字符重新转义，保证输出可以被重新解析
 impl Display for StringLiteral {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.token.literal())
+        write!(f, "\"")?;
+        write_escaped(f, &self.value)?;
+        write!(f, "\"")
+    }
+}
+
+/// 按字符串字面量的转义规则输出字符串内容（不含引号），`${` 输出为 `\${`，避免被当成插值
+pub(crate) fn write_escaped(f: &mut Formatter<'_>, value: &str) -> std::fmt::Result {
+    let mut chars = value.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\t' => write!(f, "\\t")?,
+            '\r' => write!(f, "\\r")?,
+            '\0' => write!(f, "\\0")?,
+            '$' if chars.peek() == Some(&'{') => write!(f, "\\$")?,
+            ch if ch.is_control() => write!(f, "\\u{{{:x}}}", ch as u32)?,
+            ch => write!(f, "{ch}")?,
+        }
     }
+    Ok(())
 }
 
 impl NodeInterface for StringLiteral {
@@ -1,5 +1,7 @@
 pub mod expression;
+pub mod modify;
 pub mod statement;
+pub mod visitor;
 
 #[cfg(test)]
 mod tests;
@@ -8,9 +10,11 @@ use crate::ast::expression::Expression;
 use crate::ast::statement::Statement;
 
 use crate::ast::expression::boolean::Boolean;
+use crate::error::Error;
 use crate::object::environment::Environment;
 
 use crate::object::Object;
+use crate::token::span::Span;
 use crate::token::Token;
 use derive_more::From;
 use std::fmt::{Debug, Display, Formatter};
@@ -105,15 +109,19 @@ impl Program {
         let null = crate::object::null::Null;
         let mut result: Object = null.into();
 
-        for statement in self.statements.clone().into_iter() {
-            let statement_node: Node = statement.into();
-            result = statement_node.eval(env)?;
+        for statement in self.statements.iter() {
+            result = statement.eval(env)?;
 
             match result {
                 Object::ReturnValue(value) => {
-                    tracing::error!("[eval_statement] ReturnValue is ({value:?})");
+                    tracing::trace!("[eval_statement] ReturnValue is ({value:?})");
                     return Ok(value.value().clone());
                 }
+                Object::LoopControl(value) => {
+                    return Err(
+                        Error::LoopControlOutsideLoop(value.to_string(), statement.span()).into(),
+                    );
+                }
                 _ => continue,
             }
         }
@@ -122,6 +130,17 @@ impl Program {
     }
 }
 
+impl TryFrom<Node> for Program {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Node) -> Result<Self, Self::Error> {
+        match value {
+            Node::Program(value) => Ok(value),
+            _ => Err(Error::DownCastRefProgramError.into()),
+        }
+    }
+}
+
 impl NodeInterface for Program {
     fn token_literal(&self) -> &str {
         self.token_literal()
@@ -144,6 +163,10 @@ impl Identifier {
     pub fn new(token: Token, value: String) -> Self {
         Self { token, value }
     }
+
+    pub fn span(&self) -> Span {
+        self.token.span()
+    }
 }
 
 impl From<Token> for Identifier {
@@ -185,7 +208,7 @@ impl TryFrom<Expression> for Identifier {
                 value: value.value().to_string(),
             }),
             v => {
-                tracing::error!("Expression: {v}");
+                tracing::debug!("Expression: {v}");
                 Err(anyhow::anyhow!("Expression({}) is not Identifier", v))
             }
         }
@@ -0,0 +1,60 @@
+use crate::ast::expression::Expression;
+use crate::ast::statement::block::BlockStatement;
+use crate::ast::statement::Statement;
+use crate::ast::visitor::{
+    walk_block_statement_mut, walk_expression_mut, walk_statement_mut, VisitorMut,
+};
+use crate::ast::Node;
+
+/// 修改函数，接受一个节点并返回替换后的节点
+pub type ModifierFunc<'a> = dyn FnMut(Node) -> anyhow::Result<Node> + 'a;
+
+/// modify 先递归遍历给定节点的子节点，用 modifier 的返回值替换每个子节点，
+/// 最后再对节点本身调用 modifier。
+pub fn modify(node: Node, modifier: &mut ModifierFunc) -> anyhow::Result<Node> {
+    let mut visitor = Modifier { modifier };
+    let node = match node {
+        Node::Program(mut value) => {
+            visitor.visit_program(&mut value)?;
+            value.into()
+        }
+        Node::Statement(mut value) => {
+            walk_statement_mut(&mut visitor, &mut value)?;
+            value.into()
+        }
+        Node::Expression(mut value) => {
+            walk_expression_mut(&mut visitor, &mut value)?;
+            value.into()
+        }
+        node => node,
+    };
+
+    (visitor.modifier)(node)
+}
+
+struct Modifier<'a, 'b> {
+    modifier: &'a mut ModifierFunc<'b>,
+}
+
+impl VisitorMut for Modifier<'_, '_> {
+    fn visit_statement(&mut self, statement: &mut Statement) -> anyhow::Result<()> {
+        walk_statement_mut(self, statement)?;
+        let node = (self.modifier)(std::mem::take(statement).into())?;
+        *statement = Statement::try_from(node)?;
+        Ok(())
+    }
+
+    fn visit_block_statement(&mut self, block: &mut BlockStatement) -> anyhow::Result<()> {
+        walk_block_statement_mut(self, block)?;
+        let node = (self.modifier)(std::mem::take(block).into())?;
+        *block = BlockStatement::try_from(node)?;
+        Ok(())
+    }
+
+    fn visit_expression(&mut self, expression: &mut Expression) -> anyhow::Result<()> {
+        walk_expression_mut(self, expression)?;
+        let node = (self.modifier)(std::mem::take(expression).into())?;
+        *expression = Expression::try_from(node)?;
+        Ok(())
+    }
+}
@@ -1,11 +1,11 @@
 use crate::ast::statement::Statement;
 use crate::ast::Node;
 use crate::ast::NodeInterface;
+use crate::error::Error;
 use crate::object::environment::Environment;
 use crate::object::null::Null;
 use crate::object::Object;
-use crate::object::ObjectInterface;
-use crate::object::ObjectType;
+use crate::token::span::Span;
 use crate::token::Token;
 use std::fmt::{Debug, Display, Formatter};
 
@@ -23,6 +23,14 @@ impl BlockStatement {
         }
     }
 
+    pub fn span(&self) -> Span {
+        self.statements
+            .iter()
+            .fold(self.token.span(), |span, statement| {
+                span.merge(statement.span())
+            })
+    }
+
     pub fn push_statement(&mut self, statement: Statement) {
         self.statements.push(statement);
     }
@@ -35,22 +43,26 @@ impl BlockStatement {
         &self.statements
     }
 
-    #[tracing::instrument(name = "eval_block_statement", skip(self),fields(env = %env))]
+    pub fn statements_mut(&mut self) -> &mut Vec<Statement> {
+        &mut self.statements
+    }
+
+    pub fn update_statements(&mut self, statements: Vec<Statement>) {
+        self.statements = statements;
+    }
+
+    #[tracing::instrument(level = "trace", name = "eval_block_statement", skip(self),fields(env = %env))]
     pub fn eval_block_statement(&self, env: &mut Environment) -> anyhow::Result<Object> {
         let mut result: Object = Null.into();
 
-        for statement in self.statements.clone().into_iter() {
+        for statement in self.statements.iter() {
             tracing::trace!("[eval_block_statement] statement is ({:#?})", statement);
-            let node: Node = statement.into();
-            result = node.eval(env)?;
+            result = statement.eval(env)?;
 
             tracing::trace!("[eval_block_statement] result is ({:?})", result);
-            match result.clone() {
-                Object::ReturnValue(value) => {
-                    if value.object_type() == ObjectType::Return {
-                        return Ok(value.into());
-                    }
-                }
+            match result {
+                // return 和 break/continue 都要中断块的执行，交给外层的函数或循环处理
+                Object::ReturnValue(_) | Object::LoopControl(_) => return Ok(result),
                 _ => continue,
             }
         }
@@ -59,6 +71,17 @@ impl BlockStatement {
     }
 }
 
+impl TryFrom<Node> for BlockStatement {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Node) -> Result<Self, Self::Error> {
+        match value {
+            Node::Statement(Statement::BlockStatement(value)) => Ok(value),
+            _ => Err(Error::DownCastRefBlockStatementError.into()),
+        }
+    }
+}
+
 impl Display for BlockStatement {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         for statement in self.statements.iter() {
@@ -0,0 +1,49 @@
+use crate::ast::statement::Statement;
+use crate::ast::NodeInterface;
+use crate::error::Error;
+use crate::token::span::Span;
+use crate::token::Token;
+use std::fmt::{Display, Formatter};
+
+/// break statement
+#[derive(Debug, Clone, Default, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct BreakStatement {
+    token: Token, // break 词法单元
+}
+
+impl BreakStatement {
+    pub fn new(token: Token) -> Self {
+        Self { token }
+    }
+
+    pub fn span(&self) -> Span {
+        self.token.span()
+    }
+
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+}
+
+impl Display for BreakStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{};", self.token_literal())
+    }
+}
+
+impl NodeInterface for BreakStatement {
+    fn token_literal(&self) -> &str {
+        self.token.literal()
+    }
+}
+
+impl TryFrom<Statement> for BreakStatement {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Statement) -> Result<Self, Self::Error> {
+        match value {
+            Statement::Break(value) => Ok(value),
+            unknow => Err(Error::UnknowStatement(unknow.to_string()).into()),
+        }
+    }
+}
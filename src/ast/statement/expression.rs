@@ -3,6 +3,7 @@ use crate::ast::expression::Expression;
 use crate::ast::statement::Statement;
 use crate::ast::NodeInterface;
 use crate::error::Error;
+use crate::token::span::Span;
 use crate::token::Token;
 use std::fmt::{Display, Formatter};
 
@@ -23,6 +24,10 @@ impl ExpressionStatement {
         }
     }
 
+    pub fn span(&self) -> Span {
+        self.token.span().merge(self.expression.span())
+    }
+
     pub fn token(&self) -> &Token {
         &self.token
     }
@@ -31,6 +36,10 @@ impl ExpressionStatement {
         &self.expression
     }
 
+    pub fn expression_mut(&mut self) -> &mut Expression {
+        &mut self.expression
+    }
+
     pub fn update_expression(&mut self, expression: Expression) {
         self.expression = expression;
     }
@@ -0,0 +1,111 @@
+use crate::ast::expression::Expression;
+use crate::ast::statement::block::BlockStatement;
+use crate::ast::statement::Statement;
+use crate::ast::{Identifier, NodeInterface};
+use crate::error::Error;
+use crate::token::span::Span;
+use crate::token::Token;
+use std::fmt::{Display, Formatter};
+
+/// for statement: `for (variable in iterable) { body }`
+#[derive(Debug, Clone, Default, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct ForStatement {
+    token: Token, // for 词法单元
+    variable: Identifier,
+    iterable: Box<Expression>,
+    body: BlockStatement,
+}
+
+impl ForStatement {
+    pub fn new(token: Token) -> Self {
+        Self {
+            token,
+            ..Default::default()
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        self.token
+            .span()
+            .merge(self.iterable.span())
+            .merge(self.body.span())
+    }
+
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+
+    pub fn variable(&self) -> &Identifier {
+        &self.variable
+    }
+
+    pub fn iterable(&self) -> &Expression {
+        &self.iterable
+    }
+
+    pub fn body(&self) -> &BlockStatement {
+        &self.body
+    }
+
+    pub fn variable_mut(&mut self) -> &mut Identifier {
+        &mut self.variable
+    }
+
+    pub fn iterable_mut(&mut self) -> &mut Expression {
+        &mut self.iterable
+    }
+
+    pub fn body_mut(&mut self) -> &mut BlockStatement {
+        &mut self.body
+    }
+
+    pub fn update_variable(&mut self, variable: Identifier) {
+        self.variable = variable;
+    }
+
+    pub fn update_iterable(&mut self, iterable: Expression) {
+        *self.iterable = iterable;
+    }
+
+    pub fn update_body(&mut self, body: BlockStatement) {
+        self.body = body;
+    }
+}
+
+impl Display for ForStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "for {} in {} {}",
+            self.variable, self.iterable, self.body
+        )
+    }
+}
+
+impl NodeInterface for ForStatement {
+    fn token_literal(&self) -> &str {
+        self.token.literal()
+    }
+}
+
+impl TryFrom<Statement> for ForStatement {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Statement) -> Result<Self, Self::Error> {
+        match value {
+            Statement::For(value) => Ok(value),
+            unknow => Err(Error::UnknowStatement(unknow.to_string()).into()),
+        }
+    }
+}
+
+impl TryFrom<&Statement> for ForStatement {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &Statement) -> Result<Self, Self::Error> {
+        match value {
+            Statement::For(value) => Ok(value.clone()),
+            unknow => Err(Error::UnknowStatement(unknow.to_string()).into()),
+        }
+    }
+}
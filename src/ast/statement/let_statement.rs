@@ -3,6 +3,7 @@ use crate::ast::expression::Expression;
 use crate::ast::statement::Statement;
 use crate::ast::{Identifier, NodeInterface};
 use crate::error::Error;
+use crate::token::span::Span;
 use crate::token::Token;
 use std::fmt::{Display, Formatter};
 
@@ -30,18 +31,35 @@ impl LetStatement {
         }
     }
 
+    pub fn span(&self) -> Span {
+        self.token.span().merge(self.value.span())
+    }
+
+    /// 写在 let 前面的文档注释（`///`），多行之间用换行连接
+    pub fn doc(&self) -> Option<&str> {
+        self.token.doc()
+    }
+
     pub fn value(&self) -> &Expression {
         &self.value
     }
 
+    pub fn value_mut(&mut self) -> &mut Expression {
+        &mut self.value
+    }
+
     pub fn update_expression(&mut self, value: Expression) {
-        self.value = Box::new(value);
+        *self.value = value;
     }
 
     pub fn name(&self) -> &Identifier {
         &self.name
     }
 
+    pub fn name_mut(&mut self) -> &mut Identifier {
+        &mut self.name
+    }
+
     pub fn update_identifier(&mut self, name: Identifier) {
         self.name = name;
     }
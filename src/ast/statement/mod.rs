@@ -1,14 +1,24 @@
 pub mod block;
+pub mod break_statement;
+pub mod continue_statement;
 pub mod expression;
+pub mod for_statement;
 pub mod let_statement;
 pub mod return_statement;
+pub mod while_statement;
 
 use super::Node;
 use crate::ast::statement::block::BlockStatement;
+use crate::ast::statement::break_statement::BreakStatement;
+use crate::ast::statement::continue_statement::ContinueStatement;
 use crate::ast::statement::expression::ExpressionStatement;
+use crate::ast::statement::for_statement::ForStatement;
 use crate::ast::statement::let_statement::LetStatement;
 use crate::ast::statement::return_statement::ReturnStatement;
+use crate::ast::statement::while_statement::WhileStatement;
 use crate::ast::NodeInterface;
+use crate::error::Error;
+use crate::token::span::Span;
 use derive_more::From;
 use std::fmt::{Debug, Display, Formatter};
 
@@ -18,6 +28,31 @@ pub enum Statement {
     Let(LetStatement),
     Return(ReturnStatement),
     BlockStatement(BlockStatement),
+    While(WhileStatement),
+    For(ForStatement),
+    Break(BreakStatement),
+    Continue(ContinueStatement),
+}
+
+impl Default for Statement {
+    fn default() -> Self {
+        Self::Expression(ExpressionStatement::default())
+    }
+}
+
+impl Statement {
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Expression(value) => value.span(),
+            Self::Let(value) => value.span(),
+            Self::Return(value) => value.span(),
+            Self::BlockStatement(value) => value.span(),
+            Self::While(value) => value.span(),
+            Self::For(value) => value.span(),
+            Self::Break(value) => value.span(),
+            Self::Continue(value) => value.span(),
+        }
+    }
 }
 
 impl NodeInterface for Statement {
@@ -27,6 +62,10 @@ impl NodeInterface for Statement {
             Self::Let(value) => value.token_literal(),
             Self::Return(value) => value.token_literal(),
             Self::BlockStatement(value) => value.token_literal(),
+            Self::While(value) => value.token_literal(),
+            Self::For(value) => value.token_literal(),
+            Self::Break(value) => value.token_literal(),
+            Self::Continue(value) => value.token_literal(),
         }
     }
 }
@@ -38,6 +77,10 @@ impl Display for Statement {
             Self::Let(value) => write!(f, "{value}"),
             Self::Return(value) => write!(f, "{value}"),
             Self::BlockStatement(value) => write!(f, "{value}"),
+            Self::While(value) => write!(f, "{value}"),
+            Self::For(value) => write!(f, "{value}"),
+            Self::Break(value) => write!(f, "{value}"),
+            Self::Continue(value) => write!(f, "{value}"),
         }
     }
 }
@@ -66,6 +109,29 @@ impl From<BlockStatement> for Node {
     }
 }
 
+impl From<WhileStatement> for Node {
+    fn from(value: WhileStatement) -> Self {
+        Self::Statement(Statement::While(value))
+    }
+}
+
+impl From<ForStatement> for Node {
+    fn from(value: ForStatement) -> Self {
+        Self::Statement(Statement::For(value))
+    }
+}
+
+impl TryFrom<Node> for Statement {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Node) -> Result<Self, Self::Error> {
+        match value {
+            Node::Statement(value) => Ok(value),
+            _ => Err(Error::DownCastRefStatementError.into()),
+        }
+    }
+}
+
 impl AsRef<Statement> for &Statement {
     fn as_ref(&self) -> &Statement {
         self
@@ -3,6 +3,7 @@ use crate::ast::expression::Expression;
 use crate::ast::statement::Statement;
 use crate::ast::NodeInterface;
 use crate::error::Error;
+use crate::token::span::Span;
 use crate::token::Token;
 use std::fmt::{Display, Formatter};
 
@@ -21,6 +22,10 @@ impl ReturnStatement {
         }
     }
 
+    pub fn span(&self) -> Span {
+        self.token.span().merge(self.return_value.span())
+    }
+
     pub fn token(&self) -> &Token {
         &self.token
     }
@@ -29,8 +34,12 @@ impl ReturnStatement {
         &self.return_value
     }
 
+    pub fn return_value_mut(&mut self) -> &mut Expression {
+        &mut self.return_value
+    }
+
     pub fn update_expression(&mut self, expression: Expression) {
-        self.return_value = Box::new(expression);
+        *self.return_value = expression;
     }
 
     pub fn return_value_into(self) -> Expression {
@@ -0,0 +1,94 @@
+use crate::ast::expression::Expression;
+use crate::ast::statement::block::BlockStatement;
+use crate::ast::statement::Statement;
+use crate::ast::NodeInterface;
+use crate::error::Error;
+use crate::token::span::Span;
+use crate::token::Token;
+use std::fmt::{Display, Formatter};
+
+/// while statement: `while (condition) { body }`
+#[derive(Debug, Clone, Default, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct WhileStatement {
+    token: Token, // while 词法单元
+    condition: Box<Expression>,
+    body: BlockStatement,
+}
+
+impl WhileStatement {
+    pub fn new(token: Token) -> Self {
+        Self {
+            token,
+            ..Default::default()
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        self.token
+            .span()
+            .merge(self.condition.span())
+            .merge(self.body.span())
+    }
+
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+
+    pub fn condition(&self) -> &Expression {
+        &self.condition
+    }
+
+    pub fn body(&self) -> &BlockStatement {
+        &self.body
+    }
+
+    pub fn condition_mut(&mut self) -> &mut Expression {
+        &mut self.condition
+    }
+
+    pub fn body_mut(&mut self) -> &mut BlockStatement {
+        &mut self.body
+    }
+
+    pub fn update_condition(&mut self, condition: Expression) {
+        *self.condition = condition;
+    }
+
+    pub fn update_body(&mut self, body: BlockStatement) {
+        self.body = body;
+    }
+}
+
+impl Display for WhileStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "while {} {}", self.condition, self.body)
+    }
+}
+
+impl NodeInterface for WhileStatement {
+    fn token_literal(&self) -> &str {
+        self.token.literal()
+    }
+}
+
+impl TryFrom<Statement> for WhileStatement {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Statement) -> Result<Self, Self::Error> {
+        match value {
+            Statement::While(value) => Ok(value),
+            unknow => Err(Error::UnknowStatement(unknow.to_string()).into()),
+        }
+    }
+}
+
+impl TryFrom<&Statement> for WhileStatement {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &Statement) -> Result<Self, Self::Error> {
+        match value {
+            Statement::While(value) => Ok(value.clone()),
+            unknow => Err(Error::UnknowStatement(unknow.to_string()).into()),
+        }
+    }
+}
@@ -30,3 +30,160 @@ fn test_display() {
 
     assert_eq!(format!("{program}"), "let myVar = anotherVar;");
 }
+
+#[test]
+fn test_modify() {
+    use crate::ast::expression::integer::IntegerLiteral;
+    use crate::ast::expression::Expression;
+    use crate::ast::modify::modify;
+    use crate::ast::Node;
+    use crate::lexer::lexer;
+    use crate::parser::Parser;
+    use crate::token::token_type::TokenType;
+    use crate::token::Token;
+
+    let turn_one_into_two = &mut |node: Node| -> anyhow::Result<Node> {
+        match node {
+            Node::Expression(Expression::IntegerLiteral(ref integer)) if integer.value() == 1 => {
+                let mut two = IntegerLiteral::new(Token::from_string(TokenType::INT, "2".into()));
+                two.update_value(2.into());
+                Ok(Expression::IntegerLiteral(two).into())
+            }
+            node => Ok(node),
+        }
+    };
+
+    let tests = vec![
+        ("1", "2"),
+        ("1 + 2", "(2 + 2)"),
+        ("2 + 1", "(2 + 2)"),
+        ("-1", "(-2)"),
+        ("1[1]", "(2[2])"),
+        ("if (1) { 1 } else { 1 }", "if 2 2else 2"),
+        ("return 1;", "return 2;"),
+        ("let value = 1;", "let value = 2;"),
+        ("fn() { 1 }", "fn()2"),
+        ("[1, 1]", "[2, 2]"),
+        ("{1: 1}", "{2:2}"),
+        ("add(1, 1)", "add(2, 2)"),
+    ];
+
+    for (input, expected) in tests {
+        let tokens = lexer(input).unwrap().1;
+        let program = Parser::new(tokens).unwrap().parse_program().unwrap();
+
+        let modified = modify(program.into(), turn_one_into_two).unwrap();
+
+        assert_eq!(modified.to_string(), expected);
+    }
+}
+
+#[test]
+fn test_visitor() {
+    use crate::ast::expression::Expression;
+    use crate::ast::visitor::{walk_expression_mut, Visitor, VisitorMut};
+    use crate::ast::Identifier;
+    use crate::lexer::lexer;
+    use crate::parser::Parser;
+
+    #[derive(Default)]
+    struct IdentifierCollector {
+        names: Vec<String>,
+    }
+
+    impl Visitor for IdentifierCollector {
+        fn visit_identifier(&mut self, identifier: &Identifier) -> anyhow::Result<()> {
+            self.names.push(identifier.value.clone());
+            Ok(())
+        }
+    }
+
+    struct Rename;
+
+    impl VisitorMut for Rename {
+        fn visit_expression(&mut self, expression: &mut Expression) -> anyhow::Result<()> {
+            if let Expression::Identifier(identifier) = expression {
+                identifier.value = identifier.value.to_uppercase();
+            }
+            walk_expression_mut(self, expression)
+        }
+    }
+
+    let input = r#"
+let add = fn(a, b) { a + b };
+if (add(x, [y][0]) > {"k": z}["k"]) { return -w; }
+"#;
+    let tokens = lexer(input).unwrap().1;
+    let mut program = Parser::new(tokens).unwrap().parse_program().unwrap();
+
+    let mut collector = IdentifierCollector::default();
+    collector.visit_program(&program).unwrap();
+    assert_eq!(
+        collector.names,
+        vec!["add", "a", "b", "a", "b", "add", "x", "y", "z", "w"]
+    );
+
+    Rename.visit_program(&mut program).unwrap();
+    assert_eq!(
+        program.to_string(),
+        r#"let add = fn(a,b)(A + B);if (ADD(X, ([Y][0])) > ({"k":Z}["k"])) return (-W);"#
+    );
+}
+
+#[test]
+fn test_visitor_hash_literal() {
+    use crate::ast::expression::integer::IntegerLiteral;
+    use crate::ast::expression::Expression;
+    use crate::ast::visitor::{walk_expression_mut, VisitorMut};
+    use crate::error::Error;
+    use crate::lexer::lexer;
+    use crate::parser::Parser;
+    use crate::token::token_type::TokenType;
+    use crate::token::Token;
+
+    /// 把所有整数改写成 1，遇到标识符 `fail` 时报错
+    struct IntoOne;
+
+    impl VisitorMut for IntoOne {
+        fn visit_expression(&mut self, expression: &mut Expression) -> anyhow::Result<()> {
+            match expression {
+                Expression::IntegerLiteral(_) => {
+                    let mut one =
+                        IntegerLiteral::new(Token::from_string(TokenType::INT, "1".into()));
+                    one.update_value(1.into());
+                    *expression = Expression::IntegerLiteral(one);
+                    Ok(())
+                }
+                Expression::Identifier(identifier) if identifier.value == "fail" => {
+                    Err(anyhow::anyhow!("fail"))
+                }
+                _ => walk_expression_mut(self, expression),
+            }
+        }
+    }
+
+    let parse = |input| {
+        let tokens = lexer(input).unwrap().1;
+        Parser::new(tokens).unwrap().parse_program().unwrap()
+    };
+
+    let mut program = parse(r#"{"k": 2, "v": [3]}"#);
+    IntoOne.visit_program(&mut program).unwrap();
+    assert_eq!(program.to_string(), r#"{"k":1, "v":[1]}"#);
+
+    // 出错时原来的键值对保持不变
+    let mut program = parse(r#"{1: 2, "a": fail, "b": 3}"#);
+    let original = program.to_string();
+    assert!(IntoOne.visit_program(&mut program).is_err());
+    assert_eq!(program.to_string(), original);
+
+    // 改写后的 key 重复时报错，而不是丢掉其中一个键值对
+    let mut program = parse(r#"{2: "a", 3: "b"}"#);
+    let original = program.to_string();
+    let error = IntoOne.visit_program(&mut program).unwrap_err();
+    assert!(
+        matches!(error.downcast_ref::<Error>(), Some(Error::DuplicateHashKey(key, _)) if key == "1"),
+        "{error}"
+    );
+    assert_eq!(program.to_string(), original);
+}
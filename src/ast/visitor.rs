@@ -0,0 +1,317 @@
+use crate::ast::expression::hash::HashLiteral;
+use crate::ast::expression::Expression;
+use crate::ast::statement::block::BlockStatement;
+use crate::ast::statement::Statement;
+use crate::ast::{Identifier, Program};
+use crate::error::Error;
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+
+/// 以只读方式遍历语法树。
+/// 每个 `visit_*` 的默认实现都会调用对应的 `walk_*` 继续向下递归，
+/// 实现者只需要重写关心的节点，并在需要时手动调用 `walk_*`。
+pub trait Visitor {
+    fn visit_program(&mut self, program: &Program) -> anyhow::Result<()> {
+        walk_program(self, program)
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) -> anyhow::Result<()> {
+        walk_statement(self, statement)
+    }
+
+    fn visit_block_statement(&mut self, block: &BlockStatement) -> anyhow::Result<()> {
+        walk_block_statement(self, block)
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) -> anyhow::Result<()> {
+        walk_expression(self, expression)
+    }
+
+    fn visit_identifier(&mut self, _identifier: &Identifier) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// 与 `Visitor` 相同，但可以原地改写子节点
+pub trait VisitorMut {
+    fn visit_program(&mut self, program: &mut Program) -> anyhow::Result<()> {
+        walk_program_mut(self, program)
+    }
+
+    fn visit_statement(&mut self, statement: &mut Statement) -> anyhow::Result<()> {
+        walk_statement_mut(self, statement)
+    }
+
+    fn visit_block_statement(&mut self, block: &mut BlockStatement) -> anyhow::Result<()> {
+        walk_block_statement_mut(self, block)
+    }
+
+    fn visit_expression(&mut self, expression: &mut Expression) -> anyhow::Result<()> {
+        walk_expression_mut(self, expression)
+    }
+
+    fn visit_identifier(&mut self, _identifier: &mut Identifier) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+pub fn walk_program<V: Visitor + ?Sized>(visitor: &mut V, program: &Program) -> anyhow::Result<()> {
+    for statement in program.statements.iter() {
+        visitor.visit_statement(statement)?;
+    }
+    Ok(())
+}
+
+/// 块语句作为 `Statement::BlockStatement` 出现时，只遍历其中的语句，
+/// 块本身已经通过 `visit_statement` 访问过了
+pub fn walk_statement<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    statement: &Statement,
+) -> anyhow::Result<()> {
+    match statement {
+        Statement::Expression(value) => visitor.visit_expression(value.expression()),
+        Statement::Let(value) => {
+            visitor.visit_identifier(value.name())?;
+            visitor.visit_expression(value.value())
+        }
+        Statement::Return(value) => visitor.visit_expression(value.return_value()),
+        Statement::BlockStatement(value) => walk_block_statement(visitor, value),
+        Statement::While(value) => {
+            visitor.visit_expression(value.condition())?;
+            visitor.visit_block_statement(value.body())
+        }
+        Statement::For(value) => {
+            visitor.visit_identifier(value.variable())?;
+            visitor.visit_expression(value.iterable())?;
+            visitor.visit_block_statement(value.body())
+        }
+        Statement::Break(_) | Statement::Continue(_) => Ok(()),
+    }
+}
+
+pub fn walk_block_statement<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    block: &BlockStatement,
+) -> anyhow::Result<()> {
+    for statement in block.statements() {
+        visitor.visit_statement(statement)?;
+    }
+    Ok(())
+}
+
+pub fn walk_expression<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    expression: &Expression,
+) -> anyhow::Result<()> {
+    match expression {
+        Expression::Prefix(value) => visitor.visit_expression(value.right()),
+        Expression::Infix(value) => {
+            visitor.visit_expression(value.left())?;
+            visitor.visit_expression(value.right())
+        }
+        Expression::IntegerLiteral(_)
+        | Expression::FloatLiteral(_)
+        | Expression::Boolean(_)
+        | Expression::StringLiteral(_) => Ok(()),
+        Expression::Identifier(value) => visitor.visit_identifier(value),
+        Expression::If(value) => {
+            visitor.visit_expression(value.condition())?;
+            if let Some(consequence) = value.consequence() {
+                visitor.visit_block_statement(consequence)?;
+            }
+            if let Some(alternative) = value.alternative() {
+                visitor.visit_block_statement(alternative)?;
+            }
+            Ok(())
+        }
+        Expression::FunctionLiteral(value) => {
+            for parameter in value.parameters() {
+                visitor.visit_identifier(parameter)?;
+            }
+            visitor.visit_block_statement(value.body())
+        }
+        Expression::MacroLiteral(value) => {
+            for parameter in value.parameters() {
+                visitor.visit_identifier(parameter)?;
+            }
+            visitor.visit_block_statement(value.body())
+        }
+        Expression::Call(value) => {
+            visitor.visit_expression(value.function())?;
+            for argument in value.arguments() {
+                visitor.visit_expression(argument)?;
+            }
+            Ok(())
+        }
+        Expression::InterpolatedString(value) => {
+            for expression in value.expressions() {
+                visitor.visit_expression(expression)?;
+            }
+            Ok(())
+        }
+        Expression::ArrayLiteral(value) => {
+            for element in value.elements() {
+                visitor.visit_expression(element)?;
+            }
+            Ok(())
+        }
+        Expression::Index(value) => {
+            visitor.visit_expression(value.left())?;
+            visitor.visit_expression(value.index())
+        }
+        Expression::Assign(value) => {
+            visitor.visit_expression(value.target())?;
+            visitor.visit_expression(value.value())
+        }
+        Expression::HashLiteral(value) => {
+            for (key, value) in value.pair() {
+                visitor.visit_expression(key)?;
+                visitor.visit_expression(value)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+pub fn walk_program_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    program: &mut Program,
+) -> anyhow::Result<()> {
+    for statement in program.statements.iter_mut() {
+        visitor.visit_statement(statement)?;
+    }
+    Ok(())
+}
+
+pub fn walk_statement_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    statement: &mut Statement,
+) -> anyhow::Result<()> {
+    match statement {
+        Statement::Expression(value) => visitor.visit_expression(value.expression_mut()),
+        Statement::Let(value) => {
+            visitor.visit_identifier(value.name_mut())?;
+            visitor.visit_expression(value.value_mut())
+        }
+        Statement::Return(value) => visitor.visit_expression(value.return_value_mut()),
+        Statement::BlockStatement(value) => walk_block_statement_mut(visitor, value),
+        Statement::While(value) => {
+            visitor.visit_expression(value.condition_mut())?;
+            visitor.visit_block_statement(value.body_mut())
+        }
+        Statement::For(value) => {
+            visitor.visit_identifier(value.variable_mut())?;
+            visitor.visit_expression(value.iterable_mut())?;
+            visitor.visit_block_statement(value.body_mut())
+        }
+        Statement::Break(_) | Statement::Continue(_) => Ok(()),
+    }
+}
+
+pub fn walk_block_statement_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    block: &mut BlockStatement,
+) -> anyhow::Result<()> {
+    for statement in block.statements_mut() {
+        visitor.visit_statement(statement)?;
+    }
+    Ok(())
+}
+
+pub fn walk_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut Expression,
+) -> anyhow::Result<()> {
+    match expression {
+        Expression::Prefix(value) => visitor.visit_expression(value.right_mut()),
+        Expression::Infix(value) => {
+            visitor.visit_expression(value.left_mut())?;
+            visitor.visit_expression(value.right_mut())
+        }
+        Expression::IntegerLiteral(_)
+        | Expression::FloatLiteral(_)
+        | Expression::Boolean(_)
+        | Expression::StringLiteral(_) => Ok(()),
+        Expression::Identifier(value) => visitor.visit_identifier(value),
+        Expression::If(value) => {
+            visitor.visit_expression(value.condition_mut())?;
+            if let Some(consequence) = value.consequence_mut() {
+                visitor.visit_block_statement(consequence)?;
+            }
+            if let Some(alternative) = value.alternative_mut() {
+                visitor.visit_block_statement(alternative)?;
+            }
+            Ok(())
+        }
+        Expression::FunctionLiteral(value) => {
+            for parameter in value.parameters_mut() {
+                visitor.visit_identifier(parameter)?;
+            }
+            visitor.visit_block_statement(value.body_mut())
+        }
+        Expression::MacroLiteral(value) => {
+            for parameter in value.parameters_mut() {
+                visitor.visit_identifier(parameter)?;
+            }
+            visitor.visit_block_statement(value.body_mut())
+        }
+        Expression::Call(value) => {
+            visitor.visit_expression(value.function_mut())?;
+            for argument in value.arguments_mut() {
+                visitor.visit_expression(argument)?;
+            }
+            Ok(())
+        }
+        Expression::InterpolatedString(value) => {
+            for expression in value.expressions_mut() {
+                visitor.visit_expression(expression)?;
+            }
+            Ok(())
+        }
+        Expression::ArrayLiteral(value) => {
+            for element in value.elements_mut() {
+                visitor.visit_expression(element)?;
+            }
+            Ok(())
+        }
+        Expression::Index(value) => {
+            visitor.visit_expression(value.left_mut())?;
+            visitor.visit_expression(value.index_mut())
+        }
+        Expression::Assign(value) => {
+            visitor.visit_expression(value.target_mut())?;
+            visitor.visit_expression(value.value_mut())
+        }
+        Expression::HashLiteral(value) => walk_hash_literal_mut(visitor, value),
+    }
+}
+
+/// 单独放在一个函数里，避免增大 `walk_expression_mut` 每一层递归的栈帧
+fn walk_hash_literal_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    value: &mut HashLiteral,
+) -> anyhow::Result<()> {
+    // BTreeMap 的 key 不能原地修改：先改写副本，全部成功后再重建。
+    // 出错或者改写后的 key 重复时，原来的键值对保持不变
+    let mut pairs = Vec::with_capacity(value.pair().len());
+    for (key, val) in value.pair() {
+        let (mut key, mut val) = (key.clone(), val.clone());
+        visitor.visit_expression(&mut key)?;
+        visitor.visit_expression(&mut val)?;
+        pairs.push((key, val));
+    }
+    let mut rebuilt = BTreeMap::new();
+    for (key, val) in pairs {
+        match rebuilt.entry(key) {
+            Entry::Occupied(entry) => {
+                let key = entry.key();
+                return Err(Error::DuplicateHashKey(key.to_string(), key.span()).into());
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(val);
+            }
+        }
+    }
+    *value.pair_mut() = rebuilt;
+    Ok(())
+}
@@ -0,0 +1,295 @@
+use crate::error::Error;
+use crate::token::span::Span;
+use std::fmt::Write;
+use std::io::IsTerminal;
+
+#[cfg(test)]
+mod tests;
+
+/// 一条诊断信息：错误描述、可选的源码位置以及可选的帮助提示
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    message: String,
+    span: Option<Span>,
+    help: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            span: None,
+            help: None,
+        }
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = (!span.is_unknown()).then_some(span);
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    pub fn help(&self) -> Option<&str> {
+        self.help.as_deref()
+    }
+}
+
+impl From<&Error> for Diagnostic {
+    fn from(error: &Error) -> Self {
+        let mut diagnostic = Diagnostic::new(error.to_string());
+        diagnostic.span = error.span();
+        diagnostic.help = help(error);
+        diagnostic
+    }
+}
+
+impl From<&anyhow::Error> for Diagnostic {
+    fn from(error: &anyhow::Error) -> Self {
+        match error.downcast_ref::<Error>() {
+            Some(error) => error.into(),
+            None => Diagnostic::new(error.to_string()),
+        }
+    }
+}
+
+/// 把错误展开成诊断信息列表，解析器一次收集到的多个错误会各自成为一条诊断
+pub fn collect(error: &anyhow::Error) -> Vec<Diagnostic> {
+    match error.downcast_ref::<Error>() {
+        Some(Error::ParserErrors(errors)) => errors.iter().map(Diagnostic::from).collect(),
+        _ => vec![error.into()],
+    }
+}
+
+/// 针对常见错误给出的修复建议
+fn help(error: &Error) -> Option<String> {
+    match error {
+        Error::IdentifierNotFound(name, _) => Some(format!(
+            "`{name}` is not defined, declare it first with `let {name} = ...;`"
+        )),
+        Error::NoPrefixParseFunctionFound(..) => Some("expected an expression here".into()),
+        Error::InvalidAssignmentTarget(..) => {
+            Some("only variables and index expressions like `a[i]` can be assigned".into())
+        }
+        Error::UnknownOperator {
+            left,
+            operator,
+            right,
+            ..
+        } => Some(format!(
+            "operator `{operator}` cannot be applied to `{left}` and `{right}`"
+        )),
+        Error::UnknownPrefixOperator {
+            operator, right, ..
+        } => Some(format!(
+            "operator `{operator}` cannot be applied to `{right}`"
+        )),
+        Error::TypeMismatch { left, right, .. } => Some(format!(
+            "both operands must have the same type, got `{left}` and `{right}`"
+        )),
+        Error::IntegerOverflow { .. } | Error::IntegerLiteralOutOfRange(..) => Some(format!(
+            "integers must be between {} and {}",
+            isize::MIN,
+            isize::MAX
+        )),
+        Error::NegativeShift { .. } => Some("shift amounts must be zero or positive".into()),
+        Error::CallDepthExceeded(..) => {
+            Some("check that every recursive call can reach a base case".into())
+        }
+        Error::NestingTooDeep { .. } => {
+            Some("split the expression into smaller parts with `let` bindings".into())
+        }
+        Error::InvalidEscape(..) => Some(
+            r#"supported escapes are \n \t \r \0 \\ \" \$ and \u{XXXX}; use r"..." for raw strings"#
+                .into(),
+        ),
+        Error::UnterminatedString(..) => Some("add a closing `\"`".into()),
+        Error::UnterminatedComment(..) => Some("add a closing `*/`".into()),
+        Error::InvalidNumberLiteral(..) => Some(
+            "names cannot start with a digit; `_` may only appear between digits, \
+             and `0x`, `0o`, `0b` introduce hex, octal and binary literals"
+                .into(),
+        ),
+        Error::IllegalCharacter(..) => Some("this character is not part of the language".into()),
+        Error::UnknownCommand(..) | Error::MissingCommandArgument(..) => {
+            Some("type `:help` to see all commands".into())
+        }
+        _ => None,
+    }
+}
+
+/// 输出模式：纯文本用于日志，ANSI 用于终端
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorMode {
+    #[default]
+    Plain,
+    Ansi,
+}
+
+impl ColorMode {
+    /// 输出目标是终端时使用 ANSI 颜色，否则使用纯文本
+    pub fn detect(stream: &impl IsTerminal) -> Self {
+        if stream.is_terminal() {
+            ColorMode::Ansi
+        } else {
+            ColorMode::Plain
+        }
+    }
+
+    fn paint(&self, style: &str, text: &str) -> String {
+        match self {
+            ColorMode::Plain => text.to_string(),
+            ColorMode::Ansi => format!("\x1b[{style}m{text}\x1b[0m"),
+        }
+    }
+}
+
+const BOLD_RED: &str = "1;31";
+const BOLD_BLUE: &str = "1;34";
+const BOLD: &str = "1";
+
+/// 把诊断信息渲染成类似 rustc 的报告：
+///
+/// ```text
+/// error: identifier not found: `foobar`
+///  --> script.mk:2:7
+///   |
+/// 2 |   a + foobar;
+///   |       ^^^^^^
+///   = help: `foobar` is not defined, declare it first with `let foobar = ...;`
+/// ```
+#[derive(Debug, Clone)]
+pub struct Renderer<'a> {
+    file_name: &'a str,
+    source: &'a str,
+    color: ColorMode,
+}
+
+impl<'a> Renderer<'a> {
+    pub fn new(file_name: &'a str, source: &'a str) -> Self {
+        Self {
+            file_name,
+            source,
+            color: ColorMode::Plain,
+        }
+    }
+
+    pub fn with_color(mut self, color: ColorMode) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let color = self.color;
+        let mut report = String::new();
+        let _ = writeln!(
+            report,
+            "{}{}",
+            color.paint(BOLD_RED, "error"),
+            color.paint(BOLD, &format!(": {}", diagnostic.message()))
+        );
+
+        // 位置不在这段源码里（比如 REPL 中来自其他输入的位置）时只输出消息
+        let located = diagnostic.span().and_then(|span| self.source_line(span));
+        let Some((span, line)) = located else {
+            let _ = writeln!(
+                report,
+                "{} {}",
+                color.paint(BOLD_BLUE, "-->"),
+                self.file_name
+            );
+            self.render_help(&mut report, diagnostic, 1);
+            return report;
+        };
+
+        let line_number = span.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        let _ = writeln!(
+            report,
+            "{gutter}{} {}:{span}",
+            color.paint(BOLD_BLUE, "-->"),
+            self.file_name
+        );
+
+        let bar = color.paint(BOLD_BLUE, "|");
+        let _ = writeln!(report, "{gutter} {bar}");
+        let _ = writeln!(
+            report,
+            "{} {bar} {line}",
+            color.paint(BOLD_BLUE, &line_number)
+        );
+
+        // 跨行的范围只标记到第一行的末尾
+        let line_chars = line.chars().count();
+        let width = self.source[span.start..span.end]
+            .chars()
+            .take_while(|ch| *ch != '\n')
+            .count()
+            .min(line_chars.saturating_sub(span.column - 1))
+            .max(1);
+        let _ = writeln!(
+            report,
+            "{gutter} {bar} {}{}",
+            " ".repeat(span.column - 1),
+            color.paint(BOLD_RED, &"^".repeat(width))
+        );
+
+        self.render_help(&mut report, diagnostic, gutter.len());
+        report
+    }
+
+    /// 位置所在的那一行源码，位置超出源码范围或者不在字符边界上时返回 None。
+    /// 源码以换行结尾时，末尾（EOF）的位置在最后一行的下一行，这时改为指向最后一行的末尾
+    fn source_line(&self, span: Span) -> Option<(Span, &'a str)> {
+        let source = self.source;
+        if span.line == 0
+            || span.column == 0
+            || span.start > span.end
+            || span.end > source.len()
+            || !source.is_char_boundary(span.start)
+            || !source.is_char_boundary(span.end)
+        {
+            return None;
+        }
+        if let Some(line) = source.lines().nth(span.line - 1) {
+            return (span.column - 1 <= line.chars().count()).then_some((span, line));
+        }
+        let (index, line) = source.lines().enumerate().last()?;
+        let at_end = span.start == source.len() && span.line == index + 2;
+        at_end.then(|| {
+            let column = line.chars().count() + 1;
+            (Span::new(span.start, span.end, index + 1, column), line)
+        })
+    }
+
+    /// 渲染多条诊断信息，每条之间空一行
+    pub fn render_all(&self, diagnostics: &[Diagnostic]) -> String {
+        diagnostics
+            .iter()
+            .map(|diagnostic| self.render(diagnostic))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn render_help(&self, report: &mut String, diagnostic: &Diagnostic, indent: usize) {
+        if let Some(help) = diagnostic.help() {
+            let _ = writeln!(
+                report,
+                "{}{} {help}",
+                " ".repeat(indent + 1),
+                self.color.paint(BOLD, "= help:")
+            );
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use super::{collect, ColorMode, Diagnostic, Renderer};
+use crate::error::Error;
+use crate::lexer::lexer;
+use crate::object::environment::Environment;
+use crate::parser::Parser;
+use crate::runner::eval_source;
+use crate::token::span::Span;
+
+fn test_render_parser_error() -> anyhow::Result<()> {
+    let input = "let x = 1;\nlet = 2;\nlet y = );";
+
+    let tokens = lexer(input)?.1;
+    let mut parser = Parser::new(tokens)?;
+    let error = match parser.parse_program() {
+        Ok(program) => return Err(anyhow::anyhow!("expected parse error, got {program}")),
+        Err(error) => error,
+    };
+
+    let report = Renderer::new("main.mk", input).render_all(&collect(&error));
+    let expected = "error: expected next token be `ident`, got `=` instead
+ --> main.mk:2:5
+  |
+2 | let = 2;
+  |     ^
+
+error: no prefix parse function for `)` found
+ --> main.mk:3:9
+  |
+3 | let y = );
+  |         ^
+  = help: expected an expression here
+";
+    if report != expected {
+        return Err(anyhow::anyhow!(
+            "report wrong.\nexpected:\n{expected}\ngot:\n{report}"
+        ));
+    }
+
+    Ok(())
+}
+
+fn test_render_runtime_error() -> anyhow::Result<()> {
+    let input = "let a = 1;\n  a + foobar;";
+
+    let error = match eval_source(input, &mut Environment::new(), &mut Environment::new()) {
+        Ok(value) => return Err(anyhow::anyhow!("expected runtime error, got {value}")),
+        Err(error) => error,
+    };
+
+    let report = Renderer::new("main.mk", input).render(&Diagnostic::from(&error));
+    let expected = "error: identifier not found: `foobar`
+ --> main.mk:2:7
+  |
+2 |   a + foobar;
+  |       ^^^^^^
+  = help: `foobar` is not defined, declare it first with `let foobar = ...;`
+";
+    if report != expected {
+        return Err(anyhow::anyhow!(
+            "report wrong.\nexpected:\n{expected}\ngot:\n{report}"
+        ));
+    }
+
+    Ok(())
+}
+
+fn test_render_error_at_end_of_source() -> anyhow::Result<()> {
+    // 源码以换行结尾，EOF 在最后一行的下一行，仍然指向最后一行的末尾
+    let input = "let x = 1;\nlet y = (x +\n";
+
+    let tokens = lexer(input)?.1;
+    let mut parser = Parser::new(tokens)?;
+    let error = match parser.parse_program() {
+        Ok(program) => return Err(anyhow::anyhow!("expected parse error, got {program}")),
+        Err(error) => error,
+    };
+
+    let report = Renderer::new("main.mk", input).render_all(&collect(&error));
+    let expected = "error: no prefix parse function for `eof` found
+ --> main.mk:2:13
+  |
+2 | let y = (x +
+  |             ^
+  = help: expected an expression here
+";
+    if report != expected {
+        return Err(anyhow::anyhow!(
+            "report wrong.\nexpected:\n{expected}\ngot:\n{report}"
+        ));
+    }
+
+    Ok(())
+}
+
+fn test_render_without_span() -> anyhow::Result<()> {
+    let diagnostic = Diagnostic::from(&Error::MacroMustReturnQuote).with_help("wrap it in quote()");
+
+    let report = Renderer::new("<repl>", "").render(&diagnostic);
+    let expected = "error: we only support returning AST-nodes from macros
+--> <repl>
+  = help: wrap it in quote()
+";
+    if report != expected {
+        return Err(anyhow::anyhow!(
+            "report wrong.\nexpected:\n{expected}\ngot:\n{report}"
+        ));
+    }
+
+    Ok(())
+}
+
+fn test_render_span_outside_source() -> anyhow::Result<()> {
+    let input = "f(1)\n";
+    let spans = vec![
+        // 来自更早的输入，超出了当前源码的范围
+        Span::new(40, 48, 1, 41),
+        // 落在多字节字符的中间
+        Span::new(1, 2, 1, 2),
+        Span::new(2, 3, 3, 1),
+    ];
+
+    for span in spans {
+        let diagnostic = Diagnostic::new("boom").with_span(span);
+        let report = Renderer::new("<repl>", "é(1)\n").render(&diagnostic);
+        let expected = "error: boom\n--> <repl>\n";
+        if report != expected {
+            return Err(anyhow::anyhow!(
+                "report wrong for {span:?}.\nexpected:\n{expected}\ngot:\n{report}"
+            ));
+        }
+    }
+
+    let diagnostic = Diagnostic::new("boom").with_span(Span::new(40, 48, 1, 41));
+    let report = Renderer::new("<repl>", input).render(&diagnostic);
+    if report != "error: boom\n--> <repl>\n" {
+        return Err(anyhow::anyhow!("report wrong. got:\n{report}"));
+    }
+
+    Ok(())
+}
+
+fn test_render_ansi() -> anyhow::Result<()> {
+    let input = "1 + true;";
+    let diagnostic = Diagnostic::new("boom").with_span(Span::new(4, 8, 1, 5));
+
+    let report = Renderer::new("main.mk", input)
+        .with_color(ColorMode::Ansi)
+        .render(&diagnostic);
+
+    if !report.starts_with("\x1b[1;31merror\x1b[0m") || !report.contains("\x1b[1;31m^^^^\x1b[0m") {
+        return Err(anyhow::anyhow!("ansi report wrong. got:\n{report:?}"));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_test_render_parser_error() {
+    let ret = test_render_parser_error();
+    println!("test_render_parser_error: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_render_runtime_error() {
+    let ret = test_render_runtime_error();
+    println!("test_render_runtime_error: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_render_error_at_end_of_source() {
+    let ret = test_render_error_at_end_of_source();
+    println!("test_render_error_at_end_of_source: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_render_without_span() {
+    let ret = test_render_without_span();
+    println!("test_render_without_span: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_render_span_outside_source() {
+    let ret = test_render_span_outside_source();
+    println!("test_render_span_outside_source: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_render_ansi() {
+    let ret = test_render_ansi();
+    println!("test_render_ansi: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
@@ -1,6 +1,7 @@
+use crate::token::span::Span;
 use thiserror::Error;
 
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, Error)]
 pub enum Error {
     #[error("unknow expression: `{0}`")]
     UnknownExpression(String),
@@ -53,21 +54,35 @@ pub enum Error {
     #[error("downcast_ref Object Error")]
     DownCastRefObjectError,
     #[error("not a function: `{0}`")]
-    NoFunction(String),
-    #[error("unknown operator: left(`{left}`),  operator(`{operator}`), right(`{right}`)")]
+    NoFunction(String, Span),
+    #[error("unknown operator: {left} {operator} {right}")]
     UnknownOperator {
         left: String,
         operator: String,
         right: String,
+        span: Span,
+    },
+    #[error("unknown operator: {operator}{right}")]
+    UnknownPrefixOperator {
+        operator: String,
+        right: String,
+        span: Span,
+    },
+    #[error("type mismatch: {left} {operator} {right}")]
+    TypeMismatch {
+        left: String,
+        operator: String,
+        right: String,
+        span: Span,
     },
     #[error("index operator not supported: `{0}`")]
-    IndexOperatorNotSupported(String),
+    IndexOperatorNotSupported(String, Span),
     #[error("Not Array Type")]
     NotArrayType,
     #[error("Not Integer Type")]
     NotIntegerType,
     #[error("identifier not found: `{0}`")]
-    IdentifierNotFound(String),
+    IdentifierNotFound(String, Span),
     #[error("read char error")]
     ReadCharError,
     #[error("read identifier error")]
@@ -77,7 +92,7 @@ pub enum Error {
     #[error("unknown Object type")]
     UnknownObjectType,
     #[error("wrong number of arguments. got=`{got}`, want=`{want}`")]
-    WrongNumberOfArguments { got: usize, want: usize },
+    WrongNumberOfArguments { got: usize, want: usize, span: Span },
     #[error("argument to `len` not supported, got `{got}`")]
     ArgumentNotSupported { got: String },
     #[error("argument to `first` must ARRAY, got `{got}`")]
@@ -85,13 +100,186 @@ pub enum Error {
     #[error("Cannot find `{ty}` token type")]
     CannotFindTokenType { ty: String },
     #[error("no prefix parse function for `{0}` found")]
-    NoPrefixParseFunctionFound(String),
-    #[error("Expect COLON Error")]
-    ExpectColonError,
-    #[error("Expect RBRACE and COMMA Error")]
-    ExpectBraceAndCommaError,
-    #[error("Expect RBRACE Error")]
-    ExpectRbraceError,
+    NoPrefixParseFunctionFound(String, Span),
+    #[error("cannot convert `{0}` object to ast node")]
+    CannotConvertObjectToAstNode(String),
+    #[error("we only support returning AST-nodes from macros")]
+    MacroMustReturnQuote,
     #[error("expected next token be `{expected}`, got `{got}` instead")]
-    ExpectNextToken { expected: String, got: String },
+    ExpectNextToken {
+        expected: String,
+        got: String,
+        span: Span,
+    },
+    #[error("invalid assignment target: `{0}`")]
+    InvalidAssignmentTarget(String, Span),
+    #[error("index out of range: index is `{index}` but length is `{len}`")]
+    IndexOutOfRange {
+        index: String,
+        len: usize,
+        span: Span,
+    },
+    #[error("`{0}` is not iterable")]
+    NotIterable(String, Span),
+    #[error("`{0}` outside of a loop")]
+    LoopControlOutsideLoop(String, Span),
+    #[error("maximum call depth of {0} exceeded")]
+    CallDepthExceeded(usize, Span),
+    #[error("expression nested more than {limit} levels deep")]
+    NestingTooDeep { limit: usize, span: Span },
+    #[error("division by zero: `{left} {operator} 0`")]
+    DivisionByZero {
+        left: String,
+        operator: String,
+        span: Span,
+    },
+    #[error("integer overflow: `{left} {operator} {right}`")]
+    IntegerOverflow {
+        left: String,
+        operator: String,
+        right: String,
+        span: Span,
+    },
+    #[error("negative shift amount: `{left} {operator} {right}`")]
+    NegativeShift {
+        left: String,
+        operator: String,
+        right: String,
+        span: Span,
+    },
+    #[error("integer literal `{0}` is out of range")]
+    IntegerLiteralOutOfRange(String, Span),
+    #[error("duplicate hash key `{0}` after rewriting the hash literal")]
+    DuplicateHashKey(String, Span),
+    #[error("unusable as hash key: `{0}`")]
+    UnusableAsHashKey(String, Span),
+    #[error("argument to `{name}` not supported, got `{got}`")]
+    ArgumentTypeNotSupported {
+        name: String,
+        got: String,
+        span: Span,
+    },
+    #[error("cannot convert `{value}` to {target}")]
+    CannotConvert {
+        value: String,
+        target: String,
+        span: Span,
+    },
+    #[error("invalid escape sequence `{0}`")]
+    InvalidEscape(String, Span),
+    #[error("unterminated string literal")]
+    UnterminatedString(Span),
+    #[error("invalid number literal `{0}`")]
+    InvalidNumberLiteral(String, Span),
+    #[error("illegal character `{0}`")]
+    IllegalCharacter(String, Span),
+    #[error("unterminated block comment")]
+    UnterminatedComment(Span),
+    #[error("{}", display_errors(.0))]
+    ParserErrors(Vec<Error>),
+    /// 不是真正的错误：`exit(code)` 借助错误的传播结束程序
+    #[error("exit with code {0}")]
+    Exit(i32),
+    #[error("unknown command `{0}`")]
+    UnknownCommand(String),
+    #[error("command `{0}` expects an argument")]
+    MissingCommandArgument(String),
+}
+
+fn display_errors(errors: &[Error]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl Error {
+    /// 错误在源码中的位置，没有位置信息的错误返回 None
+    pub fn span(&self) -> Option<Span> {
+        let span = match self {
+            Self::UnknownOperator { span, .. }
+            | Self::UnknownPrefixOperator { span, .. }
+            | Self::TypeMismatch { span, .. }
+            | Self::DivisionByZero { span, .. }
+            | Self::IntegerOverflow { span, .. }
+            | Self::NegativeShift { span, .. }
+            | Self::IntegerLiteralOutOfRange(_, span)
+            | Self::InvalidEscape(_, span)
+            | Self::UnterminatedString(span)
+            | Self::UnterminatedComment(span)
+            | Self::IllegalCharacter(_, span)
+            | Self::InvalidNumberLiteral(_, span)
+            | Self::IdentifierNotFound(_, span)
+            | Self::NoPrefixParseFunctionFound(_, span)
+            | Self::InvalidAssignmentTarget(_, span)
+            | Self::NestingTooDeep { span, .. }
+            | Self::NoFunction(_, span)
+            | Self::WrongNumberOfArguments { span, .. }
+            | Self::IndexOutOfRange { span, .. }
+            | Self::IndexOperatorNotSupported(_, span)
+            | Self::UnusableAsHashKey(_, span)
+            | Self::DuplicateHashKey(_, span)
+            | Self::LoopControlOutsideLoop(_, span)
+            | Self::CallDepthExceeded(_, span)
+            | Self::CannotConvert { span, .. }
+            | Self::NotIterable(_, span)
+            | Self::ArgumentTypeNotSupported { span, .. }
+            | Self::ExpectNextToken { span, .. } => *span,
+            _ => return None,
+        };
+        (!span.is_unknown()).then_some(span)
+    }
+
+    /// 为还没有位置信息的错误补上位置，已有的位置保持不变
+    pub fn with_span(mut self, new_span: Span) -> Self {
+        if let Some(span) = self.span_mut() {
+            if span.is_unknown() {
+                *span = new_span;
+            }
+        }
+        self
+    }
+
+    /// 把错误的位置整体后移，见 [`Span::offset`]
+    pub fn offset_span(mut self, bytes: usize, lines: usize) -> Self {
+        if let Some(span) = self.span_mut() {
+            *span = span.offset(bytes, lines);
+        }
+        self
+    }
+
+    fn span_mut(&mut self) -> Option<&mut Span> {
+        match self {
+            Self::UnknownOperator { span, .. }
+            | Self::UnknownPrefixOperator { span, .. }
+            | Self::TypeMismatch { span, .. }
+            | Self::DivisionByZero { span, .. }
+            | Self::IntegerOverflow { span, .. }
+            | Self::NegativeShift { span, .. }
+            | Self::IntegerLiteralOutOfRange(_, span)
+            | Self::InvalidEscape(_, span)
+            | Self::UnterminatedString(span)
+            | Self::UnterminatedComment(span)
+            | Self::IllegalCharacter(_, span)
+            | Self::InvalidNumberLiteral(_, span)
+            | Self::IdentifierNotFound(_, span)
+            | Self::NoPrefixParseFunctionFound(_, span)
+            | Self::InvalidAssignmentTarget(_, span)
+            | Self::NestingTooDeep { span, .. }
+            | Self::NoFunction(_, span)
+            | Self::WrongNumberOfArguments { span, .. }
+            | Self::IndexOutOfRange { span, .. }
+            | Self::IndexOperatorNotSupported(_, span)
+            | Self::UnusableAsHashKey(_, span)
+            | Self::DuplicateHashKey(_, span)
+            | Self::LoopControlOutsideLoop(_, span)
+            | Self::CallDepthExceeded(_, span)
+            | Self::CannotConvert { span, .. }
+            | Self::NotIterable(_, span)
+            | Self::ArgumentTypeNotSupported { span, .. }
+            | Self::ExpectNextToken { span, .. } => Some(span),
+            _ => None,
+        }
+    }
 }
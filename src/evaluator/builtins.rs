@@ -1,13 +1,13 @@
 use crate::error::Error;
 use crate::object::built_in_function::Builtin;
 use crate::object::built_in_function::{
-    array_first_element, array_last_element, array_push_element, array_rest_element, process_len,
-    puts,
+    array_first_element, array_last_element, array_push_element, array_rest_element, ceil, exit,
+    floor, process_len, puts, round, slice, sqrt, to_float, to_int,
 };
 use std::collections::HashMap;
 
 lazy_static! {
-    static ref BUILTINS: HashMap<String, Builtin> = {
+    pub static ref BUILTINS: HashMap<String, Builtin> = {
         let mut m = HashMap::new();
         m.insert("len".into(), Builtin::new(process_len));
         m.insert("first".into(), Builtin::new(array_first_element));
@@ -15,6 +15,14 @@ lazy_static! {
         m.insert("rest".into(), Builtin::new(array_rest_element));
         m.insert("push".into(), Builtin::new(array_push_element));
         m.insert("puts".into(), Builtin::new(puts));
+        m.insert("slice".into(), Builtin::new(slice));
+        m.insert("int".into(), Builtin::new(to_int));
+        m.insert("float".into(), Builtin::new(to_float));
+        m.insert("floor".into(), Builtin::new(floor));
+        m.insert("ceil".into(), Builtin::new(ceil));
+        m.insert("round".into(), Builtin::new(round));
+        m.insert("sqrt".into(), Builtin::new(sqrt));
+        m.insert("exit".into(), Builtin::new(exit));
         m
     };
 }
@@ -0,0 +1,86 @@
+use crate::ast::expression::call::Call;
+use crate::ast::expression::Expression;
+use crate::ast::modify::modify;
+use crate::ast::statement::Statement;
+use crate::ast::{Node, Program};
+use crate::error::Error;
+use crate::object::environment::Environment;
+use crate::object::r#macro::quote::Quote;
+use crate::object::r#macro::Macro;
+use crate::object::Object;
+
+impl Program {
+    /// 找到程序顶层的宏定义（`let name = macro(...) {...};`），
+    /// 把它们保存到环境中，并从 AST 中移除
+    pub fn define_macros(&mut self, env: &mut Environment) {
+        for statement in std::mem::take(&mut self.statements) {
+            if let Statement::Let(let_statement) = &statement {
+                if let Expression::MacroLiteral(macro_literal) = let_statement.value() {
+                    let macro_object = Macro::new(
+                        macro_literal.parameters().into(),
+                        macro_literal.body().clone(),
+                        env.clone(),
+                    );
+                    env.store(let_statement.name().value.clone(), macro_object.into());
+                    continue;
+                }
+            }
+            self.statements.push(statement);
+        }
+    }
+
+    /// 展开程序中所有的宏调用：参数不求值，而是以 Quote 的形式传给宏，
+    /// 宏的返回值（必须是 Quote）替换掉原来的调用节点
+    pub fn expand_macros(self, env: &mut Environment) -> anyhow::Result<Program> {
+        let expanded = modify(self.into(), &mut |node| {
+            let Node::Expression(Expression::Call(call)) = &node else {
+                return Ok(node);
+            };
+            let Some(macro_object) = macro_call(call, env) else {
+                return Ok(node);
+            };
+
+            let mut eval_env = macro_object.extend_macro_env(quote_args(call));
+            let body: Node = macro_object.body().clone().into();
+            let evaluated = match body.eval(&mut eval_env)? {
+                Object::ReturnValue(value) => value.value().clone(),
+                value => value,
+            };
+
+            match evaluated {
+                Object::Quote(quote) => Ok(quote.node().clone()),
+                _ => Err(Error::MacroMustReturnQuote.into()),
+            }
+        })?;
+
+        Program::try_from(expanded)
+    }
+}
+
+impl Macro {
+    fn extend_macro_env(&self, args: Vec<Quote>) -> Environment {
+        let env = Environment::new_enclosed_environment(self.env().clone());
+        for (param, arg) in self.parameters().iter().zip(args) {
+            env.store(param.value.clone(), arg.into());
+        }
+        env
+    }
+}
+
+fn macro_call(call: &Call, env: &Environment) -> Option<Macro> {
+    let Expression::Identifier(identifier) = call.function() else {
+        return None;
+    };
+
+    match env.get(identifier.value.clone()) {
+        Some(Object::Macro(value)) => Some(value),
+        _ => None,
+    }
+}
+
+fn quote_args(call: &Call) -> Vec<Quote> {
+    call.arguments()
+        .iter()
+        .map(|argument| Quote::new(argument.into()))
+        .collect()
+}
@@ -1,6 +1,18 @@
+use crate::ast::expression::assign::Assign;
+use crate::ast::expression::boolean::Boolean as AstBoolean;
+use crate::ast::expression::call::Call;
+use crate::ast::expression::float::FloatLiteral;
 use crate::ast::expression::hash::HashLiteral;
 use crate::ast::expression::if_expression::If;
+use crate::ast::expression::index::Index;
+use crate::ast::expression::infix::Infix;
+use crate::ast::expression::integer::IntegerLiteral;
+use crate::ast::expression::interpolated_string::InterpolatedString;
+use crate::ast::expression::prefix::Prefix;
 use crate::ast::expression::Expression;
+use crate::ast::modify::modify;
+use crate::ast::statement::for_statement::ForStatement;
+use crate::ast::statement::while_statement::WhileStatement;
 use crate::ast::statement::Statement;
 use crate::ast::NodeInterface;
 use crate::ast::{Identifier, Node};
@@ -10,120 +22,213 @@ use crate::object::array::Array;
 use crate::object::boolean::Boolean;
 use crate::object::boolean::Boolean as ObjBoolean;
 use crate::object::environment::Environment;
+use crate::object::float::Float;
 use crate::object::function::Function;
 use crate::object::hash::Hash;
 use crate::object::integer::Integer;
+use crate::object::loop_control::LoopControl;
 use crate::object::null::Null;
 use crate::object::r#macro::quote::Quote;
+use crate::object::r#macro::Macro;
 use crate::object::return_value::ReturnValue;
 use crate::object::string::StringObj;
 use crate::object::ObjectType;
 use crate::object::{Object, ObjectInterface};
+use crate::token::span::Span;
+use crate::token::token_type::TokenType;
+use crate::token::Token;
+use std::cell::Cell;
 use std::collections::BTreeMap;
 
 pub mod builtins;
+pub mod macro_expansion;
 
 #[cfg(test)]
 pub mod tests;
 
 impl Node {
-    pub fn quote(&self) -> anyhow::Result<Object> {
+    pub fn quote(&self, env: &mut Environment) -> anyhow::Result<Object> {
         match self {
             Node::Program(value) => Err(Error::UnknownTypeError(format!("{value:?}")).into()),
-            Node::Expression(value) => Ok(Quote::new(value.into()).into()),
-            Node::Statement(value) => Ok(Quote::new(value.into()).into()),
-            Node::Object(value) => Ok(Quote::new(value.into()).into()),
+            node => {
+                let node = eval_unquote_calls(node.clone(), env)?;
+                Ok(Quote::new(node).into())
+            }
         }
     }
 
     pub fn eval(&self, env: &mut Environment) -> anyhow::Result<Object> {
         match self {
-            Node::Program(ref value) => value.eval_program(env),
-            Node::Statement(ref value) => match value {
-                Statement::Expression(value) => {
-                    let expression_node: Node = value.expression().clone().into();
-                    expression_node.eval(env)
-                }
-                Statement::Let(value) => {
-                    let val_node = Node::from(value.value().clone());
-                    let val = val_node.eval(env)?;
-                    env.store(value.name().value.clone(), val);
-                    Ok(Null.into())
-                }
-                Statement::Return(value) => {
-                    let val_node = Node::from(value.return_value().clone());
-                    let val = val_node.eval(env)?;
-                    Ok(ReturnValue::new(val).into())
-                }
-                Statement::BlockStatement(value) => value.eval_block_statement(env),
-            },
-            Node::Expression(ref value) => match value {
-                Expression::Prefix(value) => {
-                    let right_node = Node::from(value.right().clone());
-                    let right = right_node.eval(env)?;
-                    Ok(right.eval_prefix_expression(value.operator()))
-                }
-                Expression::Infix(value) => {
-                    let left_node = Node::from(value.left().clone());
-                    let left = left_node.eval(env)?;
-                    let right_node = Node::from(value.right().clone());
-                    let right = right_node.eval(env)?;
-                    left.eval_infix_expression(value.operator(), right)
-                }
-                Expression::IntegerLiteral(value) => Ok(Integer::new(value.value()).into()),
-                Expression::Identifier(value) => value.eval_identifier(env),
-                Expression::Boolean(boolean) => {
-                    Ok(Object::Boolean(ObjBoolean::new(boolean.value())))
-                }
-                Expression::If(value) => value.eval_if_expression(env),
-                Expression::FunctionLiteral(value) => {
-                    let params = value.parameters();
-                    let body = value.body().clone();
-                    Ok(Function::new(params.into(), body, env.clone()).into())
-                }
-                Expression::Call(value) => {
-                    if value.function().token_literal() == "quote" {
-                        return Node::from(value.arguments()[0].clone()).quote();
-                    }
-                    let call_exp_node = Node::from(value.function().clone());
-                    let function = call_exp_node.eval(env)?;
+            Node::Program(value) => value.eval_program(env),
+            Node::Statement(value) => value.eval(env),
+            Node::Expression(value) => value.eval(env),
+            Node::Object(value) => {
+                Err(Error::UnknownTypeError(format!("object: {value:?}")).into())
+            }
+        }
+    }
+}
 
-                    let args = eval_expressions(value.arguments(), env)?;
+// 求值直接借用语法树，不为子节点克隆出新的 Node。
+// 每一层递归调用都会经过这里，各种节点分别放在单独的函数里，保持栈帧尽量小
+impl Statement {
+    pub fn eval(&self, env: &mut Environment) -> anyhow::Result<Object> {
+        match self {
+            Statement::Expression(value) => value.expression().eval(env),
+            Statement::Let(value) => {
+                let val = value.value().eval(env)?;
+                env.store(value.name().value.clone(), val);
+                Ok(Null.into())
+            }
+            Statement::Return(value) => {
+                let val = value.return_value().eval(env)?;
+                Ok(ReturnValue::new(val).into())
+            }
+            Statement::BlockStatement(value) => value.eval_block_statement(env),
+            Statement::While(value) => value.eval_while_statement(env),
+            Statement::For(value) => value.eval_for_statement(env),
+            Statement::Break(_) => Ok(LoopControl::Break.into()),
+            Statement::Continue(_) => Ok(LoopControl::Continue.into()),
+        }
+    }
+}
 
-                    function.apply_function(args)
-                }
-                Expression::StringLiteral(value) => {
-                    Ok(StringObj::new(value.value().to_string()).into())
-                }
-                Expression::ArrayLiteral(value) => {
-                    let elements = eval_expressions(value.elements(), env)?;
+impl Expression {
+    /// 表达式的嵌套层数受解析器限制，但每一层的栈帧仍然可能不小，栈快用完时同样换到新的栈段
+    pub fn eval(&self, env: &mut Environment) -> anyhow::Result<Object> {
+        stacker::maybe_grow(STACK_RED_ZONE, STACK_GROW_SIZE, || {
+            self.eval_expression(env)
+        })
+    }
 
-                    Ok(Array::new(elements.into_iter().collect()).into())
-                }
-                Expression::Index(value) => {
-                    let left_node = Node::from(value.left().clone());
-                    let left = left_node.eval(env)?;
-                    let index_node = Node::from(value.index().clone());
-                    let index = index_node.eval(env)?;
+    fn eval_expression(&self, env: &mut Environment) -> anyhow::Result<Object> {
+        match self {
+            Expression::Prefix(value) => value.eval_prefix_expression(env),
+            Expression::Infix(value) => value.eval_infix_expression(env),
+            Expression::IntegerLiteral(value) => Ok(value.value().into()),
+            Expression::FloatLiteral(value) => Ok(value.value().into()),
+            Expression::Identifier(value) => value.eval_identifier(env),
+            Expression::Boolean(boolean) => Ok(Object::Boolean(ObjBoolean::new(boolean.value()))),
+            Expression::If(value) => value.eval_if_expression(env),
+            Expression::FunctionLiteral(value) => {
+                let params = value.parameters();
+                let body = value.body().clone();
+                Ok(Function::new(params.into(), body, env.clone()).into())
+            }
+            Expression::Call(value) => value.eval_call_expression(env),
+            Expression::StringLiteral(value) => {
+                Ok(StringObj::new(value.value().to_string()).into())
+            }
+            Expression::InterpolatedString(value) => value.eval_interpolated_string(env),
+            Expression::ArrayLiteral(value) => {
+                let elements = eval_expressions(value.elements(), env)?;
+                Ok(Array::new(elements).into())
+            }
+            Expression::Index(value) => value.eval_index_expression(env),
+            Expression::HashLiteral(value) => value.eval_hash_literal(env),
+            Expression::Assign(value) => value.eval_assign_expression(env),
+            Expression::MacroLiteral(value) => {
+                let params = value.parameters();
+                let body = value.body().clone();
+                Ok(Macro::new(params.into(), body, env.clone()).into())
+            }
+        }
+    }
+}
 
-                    left.eval_index_expression(index)
-                }
-                Expression::HashLiteral(value) => value.eval_hash_literal(env),
-            },
-            Node::Object(value) => {
-                Err(Error::UnknownTypeError(format!("object: {value:?}")).into())
+impl Prefix {
+    fn eval_prefix_expression(&self, env: &mut Environment) -> anyhow::Result<Object> {
+        let right = self.right().eval(env)?;
+        right
+            .eval_prefix_expression(self.operator())
+            .map_err(|e| attach_span(e, self.span()))
+    }
+}
+
+impl Infix {
+    fn eval_infix_expression(&self, env: &mut Environment) -> anyhow::Result<Object> {
+        let left = self.left().eval(env)?;
+        // && 和 || 短路求值：左侧已经决定结果时不再计算右侧，结果总是布尔值
+        match (self.operator(), left.is_truthy()) {
+            ("&&", false) => return Ok(false.into()),
+            ("||", true) => return Ok(true.into()),
+            ("&&" | "||", _) => {
+                let right = self.right().eval(env)?;
+                return Ok(right.is_truthy().into());
             }
+            _ => {}
         }
+        let right = self.right().eval(env)?;
+        left.eval_infix_expression(self.operator(), right)
+            .map_err(|error| attach_span(error, self.span()))
     }
 }
 
+impl Call {
+    fn eval_call_expression(&self, env: &mut Environment) -> anyhow::Result<Object> {
+        if self.function().token_literal() == "quote" {
+            return match self.arguments() {
+                [argument] => Node::from(argument).quote(env),
+                arguments => Err(Error::WrongNumberOfArguments {
+                    got: arguments.len(),
+                    want: 1,
+                    span: self.span(),
+                }
+                .into()),
+            };
+        }
+        let function = self.function().eval(env)?;
+        let args = eval_expressions(self.arguments(), env)?;
+
+        function
+            .apply_function(args)
+            .map_err(|error| attach_span(error, self.span()))
+    }
+}
+
+impl InterpolatedString {
+    fn eval_interpolated_string(&self, env: &mut Environment) -> anyhow::Result<Object> {
+        let mut strings = self.strings().iter();
+        let mut result = strings.next().cloned().unwrap_or_default();
+        for (expression, string) in self.expressions().iter().zip(strings) {
+            result.push_str(&expression.eval(env)?.inspect());
+            result.push_str(string);
+        }
+        Ok(StringObj::new(result).into())
+    }
+}
+
+impl Index {
+    fn eval_index_expression(&self, env: &mut Environment) -> anyhow::Result<Object> {
+        let left = self.left().eval(env)?;
+        let index = self.index().eval(env)?;
+
+        left.eval_index_expression(index)
+            .map_err(|error| attach_span(error, self.span()))
+    }
+}
+
+/// 在被 quote 的节点中，对每个 `unquote(...)` 调用求值，并把结果转换回 AST 节点
+fn eval_unquote_calls(quoted: Node, env: &mut Environment) -> anyhow::Result<Node> {
+    modify(quoted, &mut |node| match node {
+        Node::Expression(Expression::Call(ref call)) if is_unquote_call(call) => {
+            let unquoted = Node::from(&call.arguments()[0]).eval(env)?;
+            unquoted.convert_object_to_ast_node()
+        }
+        node => Ok(node),
+    })
+}
+
+fn is_unquote_call(call: &Call) -> bool {
+    call.function().token_literal() == "unquote" && call.arguments().len() == 1
+}
+
 #[tracing::instrument(level = "trace", skip(env))]
 fn eval_expressions(exps: &[Expression], env: &mut Environment) -> anyhow::Result<Vec<Object>> {
     let mut result = vec![];
 
     for e in exps {
-        let node = Node::from(e);
-        let evaluated = node.eval(env)?;
+        let evaluated = e.eval(env)?;
         tracing::trace!("[eval_expressions] evaluated is = {:?}", evaluated);
         result.push(evaluated);
     }
@@ -136,10 +241,11 @@ impl HashLiteral {
         let mut pairs = BTreeMap::<Object, Object>::new();
 
         for (key_node, value_node) in self.pair().iter() {
-            let key_node = Node::from(key_node.clone());
             let key = key_node.eval(env)?;
-            let value_node = Node::from(value_node.clone());
             let value = value_node.eval(env)?;
+            let key = key
+                .hash_key()
+                .map_err(|error| attach_span(error, key_node.span()))?;
             pairs.insert(key, value);
         }
 
@@ -147,13 +253,116 @@ impl HashLiteral {
     }
 }
 
+impl WhileStatement {
+    pub fn eval_while_statement(&self, env: &mut Environment) -> anyhow::Result<Object> {
+        loop {
+            let condition = self.condition().eval(env)?;
+            if !condition.is_truthy() {
+                break;
+            }
+
+            match self.body().eval_block_statement(env)? {
+                Object::LoopControl(LoopControl::Break) => break,
+                value @ Object::ReturnValue(_) => return Ok(value),
+                _ => continue,
+            }
+        }
+
+        Ok(Null.into())
+    }
+}
+
+impl ForStatement {
+    /// 每次迭代都在一个新的内层环境里绑定循环变量，
+    /// 这样闭包捕获到的是当次迭代的值，而对外层变量的赋值仍然可见
+    pub fn eval_for_statement(&self, env: &mut Environment) -> anyhow::Result<Object> {
+        let iterable = self.iterable().eval(env)?;
+
+        let items = iterable
+            .iter_items()
+            .map_err(|error| attach_span(error, self.iterable().span()))?;
+        for item in items {
+            let mut loop_env = Environment::new_enclosed_environment(env.clone());
+            loop_env.store(self.variable().value.clone(), item);
+
+            match self.body().eval_block_statement(&mut loop_env)? {
+                Object::LoopControl(LoopControl::Break) => break,
+                value @ Object::ReturnValue(_) => return Ok(value),
+                _ => continue,
+            }
+        }
+
+        Ok(Null.into())
+    }
+}
+
+impl Assign {
+    pub fn eval_assign_expression(&self, env: &mut Environment) -> anyhow::Result<Object> {
+        let value = self.value().eval(env)?;
+        assign_to(self.target(), value.clone(), env)
+            .map_err(|error| attach_span(error, self.span()))?;
+        Ok(value)
+    }
+}
+
+/// 把值写回赋值目标。索引目标 `a[k1][k2] = v` 会从变量 `a` 开始自顶向下取出每一层容器，
+/// 每个容器和每个键都只求值一次，再从最内层开始逐层写回，最终更新的是变量 `a`
+fn assign_to(target: &Expression, value: Object, env: &mut Environment) -> anyhow::Result<()> {
+    let mut indexes = vec![];
+    let mut root = target;
+    while let Expression::Index(index) = root {
+        indexes.push(index);
+        root = index.left();
+    }
+
+    let Expression::Identifier(identifier) = root else {
+        return Err(Error::InvalidAssignmentTarget(root.to_string(), root.span()).into());
+    };
+
+    // 自顶向下：依次取出每一层容器并对它的键求值
+    let mut path: Vec<(&Index, Object, Object)> = vec![];
+    for index in indexes.into_iter().rev() {
+        let container = match path.last() {
+            Some((outer, container, key)) => container
+                .eval_index_expression(key.clone())
+                .map_err(|error| attach_span(error, outer.span()))?,
+            None => identifier.eval_identifier(env)?,
+        };
+        let key = index.index().eval(env)?;
+        path.push((index, container, key));
+    }
+
+    // 自底向上：把修改后的内层容器逐层写回外层容器
+    let mut value = value;
+    for (index, container, key) in path.into_iter().rev() {
+        value = container
+            .set_index(key, value)
+            .map_err(|error| attach_span(error, index.span()))?;
+    }
+
+    match env.assign(identifier.value.clone(), value) {
+        Some(_) => Ok(()),
+        None => Err(Error::IdentifierNotFound(identifier.value.clone(), identifier.span()).into()),
+    }
+}
+
 impl Function {
-    fn extend_function_env(&self, args: Vec<Object>) -> Environment {
-        let mut env = Environment::new_enclosed_environment(self.env().clone());
-        for (param_idx, param) in self.parameters().iter().enumerate() {
-            env.store(param.value.clone(), args[param_idx].clone()); // TODO need imporve
+    /// 以函数定义时捕获的环境为外层，创建一次调用使用的新环境
+    fn extend_function_env(&self, args: Vec<Object>) -> anyhow::Result<Environment> {
+        if args.len() != self.parameters().len() {
+            return Err(Error::WrongNumberOfArguments {
+                got: args.len(),
+                want: self.parameters().len(),
+                span: Span::default(),
+            }
+            .into());
         }
-        env
+
+        let env = Environment::new_enclosed_environment(self.env().clone());
+        for (param, arg) in self.parameters().iter().zip(args) {
+            env.store(param.value.clone(), arg);
+        }
+        Ok(env)
     }
 }
 
@@ -184,10 +393,16 @@ impl StringObj {
 
                 Ok(Boolean::new(left_val != right_val).into())
             }
+            // 按 Unicode 标量值逐个比较（字典序）
+            "<" => Ok(Boolean::new(self.value() < right.value()).into()),
+            ">" => Ok(Boolean::new(self.value() > right.value()).into()),
+            "<=" => Ok(Boolean::new(self.value() <= right.value()).into()),
+            ">=" => Ok(Boolean::new(self.value() >= right.value()).into()),
             _ => Err(Error::UnknownOperator {
                 left: self.object_type().to_string(),
                 operator: operator.to_string(),
                 right: right.object_type().to_string(),
+                span: Span::default(),
             }
             .into()),
         }
@@ -195,39 +410,190 @@ impl StringObj {
 }
 
 impl Integer {
-    fn eval_integer_infix_expression(&self, operator: &str, right: Integer) -> Object {
+    fn eval_integer_infix_expression(
+        &self,
+        operator: &str,
+        right: Integer,
+    ) -> anyhow::Result<Object> {
+        let value = match operator {
+            "/" | "%" if right.is_zero() => {
+                return Err(Error::DivisionByZero {
+                    left: self.to_string(),
+                    operator: operator.to_string(),
+                    span: Span::default(),
+                }
+                .into())
+            }
+            "+" => self.checked(operator, &right, self.checked_add(&right))?,
+            "-" => self.checked(operator, &right, self.checked_sub(&right))?,
+            "*" => self.checked(operator, &right, self.checked_mul(&right))?,
+            "/" => self.checked(operator, &right, self.checked_div(&right))?,
+            "%" => self.checked(operator, &right, self.checked_rem(&right))?,
+            "**" => self.eval_power(&right)?,
+            "&" => self.bitand(&right).into(),
+            "|" => self.bitor(&right).into(),
+            "^" => self.bitxor(&right).into(),
+            "<<" | ">>" => self.eval_shift(operator, &right)?,
+            "<" => (*self < right).into(),
+            ">" => (*self > right).into(),
+            "<=" => (*self <= right).into(),
+            ">=" => (*self >= right).into(),
+            "==" => (*self == right).into(),
+            "!=" => (*self != right).into(),
+            _ => {
+                return Err(Error::UnknownOperator {
+                    left: self.object_type().to_string(),
+                    operator: operator.to_string(),
+                    right: right.object_type().to_string(),
+                    span: Span::default(),
+                }
+                .into())
+            }
+        };
+        Ok(value)
+    }
+
+    /// 指数为负数时结果不是整数，按浮点数计算
+    fn eval_power(&self, right: &Integer) -> anyhow::Result<Object> {
+        if right.is_negative() {
+            return Ok(self.to_f64().powf(right.to_f64()).into());
+        }
+        let exponent = right.to_isize().and_then(|value| u32::try_from(value).ok());
+        self.checked(
+            "**",
+            right,
+            exponent.and_then(|value| self.checked_pow(value)),
+        )
+    }
+
+    /// 移位数量不能是负数。数量大到放不进 u32 时，左移一定溢出，
+    /// 右移等价于移出全部的位
+    fn eval_shift(&self, operator: &str, right: &Integer) -> anyhow::Result<Object> {
+        if right.is_negative() {
+            return Err(Error::NegativeShift {
+                left: self.to_string(),
+                operator: operator.to_string(),
+                right: right.to_string(),
+                span: Span::default(),
+            }
+            .into());
+        }
+        let amount = right.to_isize().and_then(|value| u32::try_from(value).ok());
         match operator {
-            "+" => Integer::new(self.value() + right.value()).into(),
-            "-" => Integer::new(self.value() - right.value()).into(),
-            "*" => Integer::new(self.value() * right.value()).into(),
-            "/" => Integer::new(self.value() / right.value()).into(),
-            "<" => (self.value() < right.value()).into(),
-            ">" => (self.value() > right.value()).into(),
-            "==" => (self.value() == right.value()).into(),
-            "!=" => (self.value() != right.value()).into(),
-            _ => Null.into(),
+            "<<" => self.checked(
+                operator,
+                right,
+                amount.and_then(|value| self.checked_shl(value)),
+            ),
+            _ => Ok(self.shr(amount.unwrap_or(u32::MAX)).into()),
+        }
+    }
+
+    /// 溢出时返回带有两个操作数的 IntegerOverflow 错误。
+    /// 开启 bigint 特性后结果会自动提升，只有 `**` 和 `<<` 的结果超过 `MAX_BIGINT_BITS` 位时才会走到这里
+    fn checked(
+        &self,
+        operator: &str,
+        right: &Integer,
+        value: Option<Integer>,
+    ) -> anyhow::Result<Object> {
+        match value {
+            Some(value) => Ok(value.into()),
+            None => Err(Error::IntegerOverflow {
+                left: self.to_string(),
+                operator: operator.to_string(),
+                right: right.to_string(),
+                span: Span::default(),
+            }
+            .into()),
         }
     }
 }
 
+impl Float {
+    /// 按 IEEE 754 计算，除以零得到无穷大或 NaN，NaN 和任何值都不相等
+    fn eval_float_infix_expression(&self, operator: &str, right: Float) -> anyhow::Result<Object> {
+        let (left_val, right_val) = (self.value(), right.value());
+        let value = match operator {
+            "+" => (left_val + right_val).into(),
+            "-" => (left_val - right_val).into(),
+            "*" => (left_val * right_val).into(),
+            "/" => (left_val / right_val).into(),
+            "%" => (left_val % right_val).into(),
+            "**" => left_val.powf(right_val).into(),
+            "<" => (left_val < right_val).into(),
+            ">" => (left_val > right_val).into(),
+            "<=" => (left_val <= right_val).into(),
+            ">=" => (left_val >= right_val).into(),
+            "==" => (left_val == right_val).into(),
+            "!=" => (left_val != right_val).into(),
+            _ => {
+                return Err(Error::UnknownOperator {
+                    left: self.object_type().to_string(),
+                    operator: operator.to_string(),
+                    right: right.object_type().to_string(),
+                    span: Span::default(),
+                }
+                .into())
+            }
+        };
+        Ok(value)
+    }
+}
+
+/// 整数和浮点数都支持的算术运算符和比较运算符
+fn is_numeric_operator(operator: &str) -> bool {
+    matches!(
+        operator,
+        "+" | "-" | "*" | "/" | "%" | "**" | "<" | ">" | "<=" | ">=" | "==" | "!="
+    )
+}
+
 impl If {
     pub fn eval_if_expression(&self, env: &mut Environment) -> anyhow::Result<Object> {
-        let node = Node::from(self.condition().clone());
-        let condition = node.eval(env)?;
-
-        if condition.is_truthy() {
-            let node: Node = self.consequence().clone().unwrap().into();
-            node.eval(env)
-        } else if self.alternative().is_some() {
-            let node: Node = self.alternative().clone().unwrap().into();
-            node.eval(env)
-        } else {
-            Ok(Null.into())
+        let condition = self.condition().eval(env)?;
+
+        let branch = match condition.is_truthy() {
+            true => self.consequence(),
+            false => self.alternative(),
+        };
+        match branch {
+            Some(block) => block.eval_block_statement(env),
+            None => Ok(Null.into()),
         }
     }
 }
 
 impl Object {
+    pub fn convert_object_to_ast_node(&self) -> anyhow::Result<Node> {
+        match self {
+            Object::Integer(value) => {
+                let token = Token::from_string(TokenType::INT, value.to_string());
+                let mut literal = IntegerLiteral::new(token);
+                literal.update_value(value.clone());
+                Ok(Expression::IntegerLiteral(literal).into())
+            }
+            Object::Float(value) => {
+                let token = Token::from_string(TokenType::FLOAT, value.to_string());
+                let mut literal = FloatLiteral::new(token);
+                literal.update_value(*value);
+                Ok(Expression::FloatLiteral(literal).into())
+            }
+            Object::Boolean(value) => {
+                let token = if value.value() {
+                    Token::from_string(TokenType::TRUE, "true".into())
+                } else {
+                    Token::from_string(TokenType::FALSE, "false".into())
+                };
+                Ok(Expression::Boolean(AstBoolean::new(token, value.value())).into())
+            }
+            Object::Quote(value) => Ok(value.node().clone()),
+            value => {
+                Err(Error::CannotConvertObjectToAstNode(value.object_type().to_string()).into())
+            }
+        }
+    }
+
     pub fn is_truthy(&self) -> bool {
         match self {
             Object::Boolean(boolean) => boolean.value(),
@@ -242,21 +608,38 @@ impl Object {
         };
 
         let idx = match index {
-            Object::Integer(integ) => integ.value(),
+            // 超出 isize 的下标一定越界
+            Object::Integer(integ) => match integ.to_isize() {
+                Some(idx) => idx,
+                None => return Ok(Null.into()),
+            },
             _ => return Err(Error::NotIntegerType.into()),
         };
 
-        let max = array_object.len() - 1;
-        if idx < 0 || idx as usize > max {
+        if idx < 0 || idx as usize >= array_object.len() {
             return Ok(Null.into());
         }
 
         Ok(array_object[idx as usize].clone())
     }
 
+    /// 字符串按字符下标取值，得到只有一个字符的字符串，越界时返回 null
+    pub fn eval_string_index_expression(&self, index: Object) -> anyhow::Result<Object> {
+        let string = StringObj::try_from(self.clone())?;
+        let ch = Integer::try_from(index)?
+            .to_isize()
+            .and_then(|idx| usize::try_from(idx).ok())
+            .and_then(|idx| string.char_at(idx));
+
+        match ch {
+            Some(ch) => Ok(ch.into()),
+            None => Ok(Null.into()),
+        }
+    }
+
     pub fn eval_hash_index_expression(&self, index: Object) -> anyhow::Result<Object> {
         let hash_object = Hash::try_from(self.clone())?;
-        let pair = hash_object.pairs().get(&index);
+        let pair = hash_object.pairs().get(&index.hash_key()?);
         if pair.is_none() {
             return Ok(Null.into());
         }
@@ -264,21 +647,91 @@ impl Object {
         Ok(pair.unwrap().clone())
     }
 
-    #[tracing::instrument(name = "eval_index_expression", skip(self), fields(index = %index))]
+    /// 作为哈希键时使用的值，浮点数的规则见 `Float::hash_key`
+    pub fn hash_key(&self) -> anyhow::Result<Object> {
+        match self {
+            Object::Float(value) => value.hash_key(),
+            _ => Ok(self.clone()),
+        }
+    }
+
+    /// for 循环依次取出的元素：数组的元素、哈希的键、字符串的字符
+    pub fn iter_items(&self) -> anyhow::Result<Vec<Object>> {
+        match self {
+            Object::Array(array) => Ok(array.elements().clone()),
+            Object::Hash(hash) => Ok(hash.pairs().keys().cloned().collect()),
+            Object::String(string) => Ok(string
+                .value()
+                .chars()
+                .map(|ch| StringObj::new(ch.to_string()).into())
+                .collect()),
+            _ => Err(Error::NotIterable(self.object_type().to_string(), Span::default()).into()),
+        }
+    }
+
+    /// 返回把 `index` 位置替换成 `value` 之后的新容器
+    pub fn set_index(self, index: Object, value: Object) -> anyhow::Result<Object> {
+        match (self, index) {
+            (Object::Array(mut array), Object::Integer(index)) => {
+                let len = array.len();
+                let idx = match index.to_isize() {
+                    Some(idx) if idx >= 0 && (idx as usize) < len => idx as usize,
+                    _ => {
+                        return Err(Error::IndexOutOfRange {
+                            index: index.to_string(),
+                            len,
+                            span: Span::default(),
+                        }
+                        .into())
+                    }
+                };
+                array.elements_mut()[idx] = value;
+                Ok(array.into())
+            }
+            (Object::Hash(mut hash), key) => {
+                hash.pairs_mut().insert(key.hash_key()?, value);
+                Ok(hash.into())
+            }
+            (container, _) => Err(Error::IndexOperatorNotSupported(
+                container.object_type().to_string(),
+                Span::default(),
+            )
+            .into()),
+        }
+    }
+
+    #[tracing::instrument(level = "trace", name = "eval_index_expression", skip(self), fields(index = %index))]
     pub fn eval_index_expression(&self, index: Object) -> anyhow::Result<Object> {
         if self.object_type() == ObjectType::Array && index.object_type() == ObjectType::Integer {
             self.eval_array_index_expression(index)
         } else if self.object_type() == ObjectType::Hash {
             self.eval_hash_index_expression(index)
+        } else if self.object_type() == ObjectType::String
+            && index.object_type() == ObjectType::Integer
+        {
+            self.eval_string_index_expression(index)
         } else {
-            Err(Error::IndexOperatorNotSupported(self.object_type().to_string()).into())
+            Err(
+                Error::IndexOperatorNotSupported(self.object_type().to_string(), Span::default())
+                    .into(),
+            )
         }
     }
 
-    fn eval_minus_prefix_operator_expression(&self) -> Object {
+    fn eval_minus_prefix_operator_expression(&self) -> anyhow::Result<Object> {
         match self {
-            Object::Integer(value) => Integer::new(-value.value()).into(),
-            _ => Null.into(),
+            // -x 等价于 0 - x，溢出时报告的也是这个减法
+            Object::Integer(value) => Integer::new(0).checked("-", value, value.checked_neg()),
+            Object::Float(value) => Ok(Float::new(-value.value()).into()),
+            _ => Err(self.unknown_prefix_operator("-").into()),
+        }
+    }
+
+    fn unknown_prefix_operator(&self, operator: &str) -> Error {
+        Error::UnknownPrefixOperator {
+            operator: operator.to_string(),
+            right: self.object_type().to_string(),
+            span: Span::default(),
         }
     }
 
@@ -293,12 +746,13 @@ impl Object {
                 }
             }
             Object::Integer(value) => {
-                if value.value() != 0 {
+                if !value.is_zero() {
                     false.into()
                 } else {
                     true.into()
                 }
             }
+            Object::Float(value) => (value.value() == 0.0).into(),
             Object::Null(_) => true.into(),
             _ => false.into(),
         }
@@ -307,61 +761,150 @@ impl Object {
     pub fn eval_infix_expression(&self, operator: &str, right: Object) -> anyhow::Result<Object> {
         match (self.clone(), right) {
             (Object::Integer(left_value), Object::Integer(right_value)) => {
-                Ok(left_value.eval_integer_infix_expression(operator, right_value))
+                left_value.eval_integer_infix_expression(operator, right_value)
+            }
+            (Object::Float(left), Object::Float(right)) => {
+                left.eval_float_infix_expression(operator, right)
+            }
+            // 整数和浮点数混合进行算术运算或比较时，整数先提升为浮点数；
+            // 其他运算符按原来的类型报错
+            (Object::Integer(left), Object::Float(right)) if is_numeric_operator(operator) => {
+                Float::new(left.to_f64()).eval_float_infix_expression(operator, right)
             }
-            (Object::Boolean(left_value), Object::Boolean(right_value)) if operator == "==" => {
-                Ok((left_value.value() == right_value.value()).into())
+            (Object::Float(left), Object::Integer(right)) if is_numeric_operator(operator) => {
+                left.eval_float_infix_expression(operator, Float::new(right.to_f64()))
             }
-            (Object::Boolean(left_value), Object::Boolean(right_value)) if operator == "!=" => {
-                Ok((left_value.value() != right_value.value()).into())
+            (
+                left @ (Object::Integer(_) | Object::Float(_)),
+                right @ (Object::Integer(_) | Object::Float(_)),
+            ) => Err(Error::UnknownOperator {
+                left: left.object_type().to_string(),
+                operator: operator.to_string(),
+                right: right.object_type().to_string(),
+                span: Span::default(),
             }
+            .into()),
             (Object::String(left), Object::String(right)) => {
                 left.eval_string_infix_expression(operator, right)
             }
-            (_, _) => Ok(Null.into()),
+            // 其余类型只支持按值比较相等，类型不同的值永远不相等
+            (left, right) if operator == "==" => Ok((left == right).into()),
+            (left, right) if operator == "!=" => Ok((left != right).into()),
+            (left, right) if left.object_type() != right.object_type() => {
+                Err(Error::TypeMismatch {
+                    left: left.object_type().to_string(),
+                    operator: operator.to_string(),
+                    right: right.object_type().to_string(),
+                    span: Span::default(),
+                }
+                .into())
+            }
+            (left, right) => Err(Error::UnknownOperator {
+                left: left.object_type().to_string(),
+                operator: operator.to_string(),
+                right: right.object_type().to_string(),
+                span: Span::default(),
+            }
+            .into()),
         }
     }
 
-    pub fn eval_prefix_expression(&self, operator: &str) -> Object {
+    pub fn eval_prefix_expression(&self, operator: &str) -> anyhow::Result<Object> {
         match operator {
-            "!" => self.eval_bang_operator_expression(),
+            "!" => Ok(self.eval_bang_operator_expression()),
             "-" => self.eval_minus_prefix_operator_expression(),
-            _ => Null.into(),
+            "~" => match self {
+                Object::Integer(value) => Ok(value.not().into()),
+                _ => Err(self.unknown_prefix_operator(operator).into()),
+            },
+            _ => Err(self.unknown_prefix_operator(operator).into()),
         }
     }
 
-    #[tracing::instrument(name = "apply_function", skip(self), fields(self = ?self, args = ?args))]
+    #[tracing::instrument(level = "trace", name = "apply_function", skip(self), fields(self = ?self, args = ?args))]
     pub fn apply_function(&self, args: Vec<Object>) -> anyhow::Result<Object> {
-        match self.clone() {
+        match self {
             Object::Function(fn_value) => {
                 tracing::trace!("[apply_function] function is {:#?}", fn_value);
 
-                let mut extend_env = fn_value.extend_function_env(args);
+                let mut extend_env = fn_value.extend_function_env(args)?;
                 tracing::trace!("[apply_function] extend_env is {:?}", extend_env);
 
-                let fn_value: Node = fn_value.body().clone().into();
-                let evaluated = fn_value.eval(&mut extend_env)?;
+                let _depth = CallDepth::enter()?;
+                // 栈快用完时在堆上分配新的栈段继续求值，深递归不会让进程崩溃
+                let evaluated = stacker::maybe_grow(STACK_RED_ZONE, STACK_GROW_SIZE, || {
+                    fn_value.body().eval_block_statement(&mut extend_env)
+                })?;
                 tracing::trace!("[apply_function] call function result is {}", evaluated);
 
-                Ok(evaluated)
+                // return 只结束当前函数，不能继续向外传播
+                match evaluated {
+                    Object::ReturnValue(value) => Ok(value.value().clone()),
+                    Object::LoopControl(value) => Err(Error::LoopControlOutsideLoop(
+                        value.to_string(),
+                        Span::default(),
+                    )
+                    .into()),
+                    value => Ok(value),
+                }
             }
             Object::Builtin(built_in) => (built_in.value())(args),
-            _ => Err(Error::NoFunction(self.object_type().to_string()).into()),
+            _ => Err(Error::NoFunction(self.object_type().to_string(), Span::default()).into()),
         }
     }
 }
 
+/// 函数调用的最大嵌套深度，超过时报错而不是无限地占用内存
+pub const MAX_CALL_DEPTH: usize = 2_000;
+/// 剩余的栈空间少于这个值时分配新的栈段
+pub(crate) const STACK_RED_ZONE: usize = 256 * 1024;
+/// 每次新分配的栈段大小
+pub(crate) const STACK_GROW_SIZE: usize = 4 * 1024 * 1024;
+
+thread_local! {
+    static CALL_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// 当前线程中正在执行的函数调用层数，离开作用域时自动减一
+struct CallDepth;
+
+impl CallDepth {
+    fn enter() -> anyhow::Result<Self> {
+        CALL_DEPTH.with(|depth| {
+            if depth.get() >= MAX_CALL_DEPTH {
+                return Err(Error::CallDepthExceeded(MAX_CALL_DEPTH, Span::default()).into());
+            }
+            depth.set(depth.get() + 1);
+            Ok(CallDepth)
+        })
+    }
+}
+
+impl Drop for CallDepth {
+    fn drop(&mut self) {
+        CALL_DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
 impl Identifier {
     pub fn eval_identifier(&self, env: &mut Environment) -> anyhow::Result<Object> {
         let val = env.get(self.value.clone());
         if let Some(val) = val {
-            return Ok(val.clone());
+            return Ok(val);
         }
 
         if let Ok(builtin) = lookup_builtin(self.value.as_str()) {
             return Ok(builtin.into());
         }
 
-        Err(Error::IdentifierNotFound(self.value.clone()).into())
+        Err(Error::IdentifierNotFound(self.value.clone(), self.span()).into())
+    }
+}
+
+/// 把节点的位置补到求值过程中产生的错误上
+fn attach_span(error: anyhow::Error, span: Span) -> anyhow::Error {
+    match error.downcast::<Error>() {
+        Ok(error) => error.with_span(span).into(),
+        Err(error) => error,
     }
 }
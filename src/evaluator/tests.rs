@@ -1,4 +1,6 @@
-use crate::ast::Node;
+use crate::ast::{Node, Program};
+use crate::error::Error;
+use crate::evaluator::MAX_CALL_DEPTH;
 use crate::lexer::lexer;
 use crate::object::array::Array;
 use crate::object::boolean::Boolean;
@@ -8,9 +10,12 @@ use crate::object::hash::Hash;
 use crate::object::integer::Integer;
 use crate::object::null::Null;
 use crate::object::r#macro::quote::Quote;
+use crate::object::r#macro::Macro;
 use crate::object::string::StringObj;
 use crate::object::Object;
-use crate::parser::Parser;
+use crate::parser::{Parser, MAX_NESTING_DEPTH};
+use crate::runner;
+use crate::token::span::Span;
 
 use std::collections::BTreeMap;
 
@@ -72,10 +77,10 @@ fn test_integer_object(obj: Object, expected: isize) -> anyhow::Result<bool> {
     let value = Integer::try_from(obj);
     match value {
         Ok(integer) => {
-            if integer.value() != expected {
+            if integer != expected {
                 eprintln!(
                     "object has wrong value. got = {:?}, want = {expected:?}",
-                    integer.value()
+                    integer
                 );
                 Ok(false)
             } else {
@@ -296,12 +301,32 @@ if (10 > 1) {
         },
         Test {
             input: "foobar",
-            expected_message: "identifier not found: foobar",
+            expected_message: "identifier not found: `foobar`",
         },
         Test {
             input: r#""Hello" - "World""#,
             expected_message: "unknown operator: STRING - STRING",
         },
+        Test {
+            input: r#"-"Hello""#,
+            expected_message: "unknown operator: -STRING",
+        },
+        Test {
+            input: r#"5 * "Hello""#,
+            expected_message: "type mismatch: INTEGER * STRING",
+        },
+        Test {
+            input: "true < false",
+            expected_message: "unknown operator: BOOLEAN < BOOLEAN",
+        },
+        Test {
+            input: "[1] + [2]",
+            expected_message: "unknown operator: ARRAY + ARRAY",
+        },
+        Test {
+            input: "let f = fn(x) { x + true }; f(1)",
+            expected_message: "type mismatch: INTEGER + BOOLEAN",
+        },
     ];
 
     for tt in tests {
@@ -309,15 +334,17 @@ if (10 > 1) {
 
         match evaluated {
             Ok(value) => {
-                eprintln!("no error object returned. got = {value:?}");
-                continue;
+                return Err(anyhow::anyhow!(
+                    "no error object returned for {}. got = {value:?}",
+                    tt.input
+                ));
             }
             Err(err) => {
                 if format!("{}", err) != tt.expected_message {
-                    eprintln!(
+                    return Err(anyhow::anyhow!(
                         "wrong error message. expected = {}, got = {err}",
                         tt.expected_message
-                    )
+                    ));
                 }
             }
         }
@@ -325,6 +352,28 @@ if (10 > 1) {
     Ok(())
 }
 
+fn test_equality_between_types() -> anyhow::Result<()> {
+    let tests = vec![
+        ("1 == true", false),
+        ("1 != true", true),
+        (r#""1" == 1"#, false),
+        ("[1, 2] == [1, 2]", true),
+        ("[1, 2] != [2, 1]", true),
+        ("if (false) { 1 } == if (false) { 2 }", true),
+    ];
+
+    for (input, expected) in tests {
+        let evaluated = test_eval(input)?;
+        if evaluated != Object::from(expected) {
+            return Err(anyhow::anyhow!(
+                "wrong result for {input}. expected = {expected}, got = {evaluated}"
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 fn test_let_statements() -> anyhow::Result<()> {
     struct Test<'a> {
         input: &'a str,
@@ -445,6 +494,821 @@ addTwo(2);"#;
     Ok(())
 }
 
+fn test_recursive_functions() -> anyhow::Result<()> {
+    let tests = vec![
+        (
+            r#"
+let fib = fn(n) {
+    if (n < 2) { return n; }
+    fib(n - 1) + fib(n - 2);
+};
+fib(15);"#,
+            610,
+        ),
+        (
+            r#"
+let isEven = fn(n) { if (n == 0) { 1 } else { isOdd(n - 1) } };
+let isOdd = fn(n) { if (n == 0) { 0 } else { isEven(n - 1) } };
+isEven(10) + isOdd(7);"#,
+            2,
+        ),
+        (
+            r#"
+let getX = fn() { x };
+let x = 5;
+getX();"#,
+            5,
+        ),
+        (
+            r#"
+let makeCounter = fn() {
+    let count = fn(n) { if (n == 0) { 0 } else { 1 + count(n - 1) } };
+    count;
+};
+makeCounter()(20);"#,
+            20,
+        ),
+    ];
+
+    for (input, expected) in tests {
+        if !test_integer_object(test_eval(input)?, expected)? {
+            return Err(anyhow::anyhow!("wrong result for `{input}`"));
+        }
+    }
+
+    Ok(())
+}
+
+fn test_named_functions_release_environment() -> anyhow::Result<()> {
+    let input = r#"
+let f = fn(n) { if (n == 0) { 0 } else { f(n - 1) } };
+let g = fn() { 1 };
+g = fn() { g };
+let unless = macro(condition, consequence) { quote(if (!(unquote(condition))) { unquote(consequence) }) };
+let make = fn() { let h = fn() { h }; h };
+unless(false, if (g()() == g && make()() != g) { f(3) + 1 });"#;
+
+    let env = Environment::new();
+    let macro_env = Environment::new();
+    let (scope, macro_scope) = (env.downgrade(), macro_env.downgrade());
+    let evaluated = runner::eval_source(input, &mut env.clone(), &mut macro_env.clone())?;
+    if !test_integer_object(evaluated, 1)? {
+        return Err(anyhow::anyhow!("wrong result for `{input}`"));
+    }
+
+    // 保存在自己捕获的作用域里的函数和宏不会让作用域一直存活
+    drop((env, macro_env));
+    if scope.is_alive() || macro_scope.is_alive() {
+        return Err(anyhow::anyhow!(
+            "environment leaked through a named function"
+        ));
+    }
+
+    Ok(())
+}
+
+fn test_call_depth_limit() -> anyhow::Result<()> {
+    let input = "let f = fn(n) { f(n + 1) }; f(0);";
+    match test_eval(input) {
+        Ok(value) => return Err(anyhow::anyhow!("expected error, got {value}")),
+        Err(error) => match error.downcast::<Error>()? {
+            Error::CallDepthExceeded(depth, _) if depth == MAX_CALL_DEPTH => {}
+            error => return Err(anyhow::anyhow!("wrong error: {error:?}")),
+        },
+    }
+
+    // 出错之后调用层数要恢复，之后的调用不受影响
+    let input = format!(
+        "let count = fn(n) {{ if (n == 0) {{ 0 }} else {{ 1 + count(n - 1) }} }}; count({});",
+        MAX_CALL_DEPTH - 1
+    );
+    if !test_integer_object(test_eval(&input)?, (MAX_CALL_DEPTH - 1) as isize)? {
+        return Err(anyhow::anyhow!("wrong result for `{input}`"));
+    }
+
+    Ok(())
+}
+
+fn test_nesting_depth_limit() -> anyhow::Result<()> {
+    let minus = "-".repeat(700);
+    let terms = vec!["1"; MAX_NESTING_DEPTH * 2].join(" + ");
+    let parens = format!("{}1{}", "(".repeat(1000), ")".repeat(1000));
+    let tests = [
+        format!("let f = fn() {{ {minus}1 }}; f()"),
+        format!("let x = {terms};"),
+        format!("let x = {parens};"),
+    ];
+
+    for input in tests {
+        match test_eval(&input).map_err(|error| error.downcast::<Error>()) {
+            Err(Ok(Error::ParserErrors(errors)))
+                if matches!(
+                    errors.as_slice(),
+                    [Error::NestingTooDeep { limit, .. }] if *limit == MAX_NESTING_DEPTH
+                ) => {}
+            other => return Err(anyhow::anyhow!("wrong result for deep input: {other:?}")),
+        }
+    }
+
+    // 限制以内的嵌套可以正常解析和求值。test_eval 会输出整棵语法树，这里直接求值
+    let depth = MAX_NESTING_DEPTH - 8;
+    let tests = [
+        (format!("let f = fn() {{ {}1 }}; f()", "-".repeat(depth)), 1),
+        (vec!["1"; depth].join(" + "), depth as isize),
+        (format!("{}1{}", "(".repeat(depth), ")".repeat(depth)), 1),
+    ];
+
+    for (input, expected) in tests {
+        let mut env = Environment::new();
+        let evaluated = runner::eval_source(&input, &mut env, &mut Environment::new())?;
+        if !test_integer_object(evaluated, expected)? {
+            return Err(anyhow::anyhow!(
+                "wrong result for {depth} nested expressions"
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn test_wrong_number_of_function_arguments() -> anyhow::Result<()> {
+    let input = "let add = fn(a, b) { a + b }; add(1);";
+
+    match test_eval(input) {
+        Ok(value) => Err(anyhow::anyhow!("expected error, got {value}")),
+        Err(error) => match error.downcast::<Error>()? {
+            Error::WrongNumberOfArguments {
+                got: 1, want: 2, ..
+            } => Ok(()),
+            error => Err(anyhow::anyhow!("wrong error. got = {error:?}")),
+        },
+    }
+}
+
+fn test_assign_expressions() -> anyhow::Result<()> {
+    let tests = vec![
+        ("let x = 1; x = x + 1; x;", 2),
+        ("let x = 1; x = 5;", 5),
+        ("let a = 1; let b = 2; a = b = 3; a + b;", 6),
+        ("let x = 1; let f = fn() { x = 10; }; f(); x;", 10),
+        (
+            r#"
+let makeCounter = fn() {
+    let count = 0;
+    fn() { count = count + 1; count };
+};
+let counter = makeCounter();
+counter();
+counter();
+counter();"#,
+            3,
+        ),
+        ("let x = 1; let f = fn(x) { x = 10; }; f(2); x;", 1),
+        ("let arr = [1, 2, 3]; arr[1] = 5; arr[1] + arr[0];", 6),
+        ("let m = [[1, 2], [3, 4]]; m[1][0] = 9; m[1][0];", 9),
+        (
+            r#"let h = {"a": 1}; h["a"] = 2; h["b"] = 3; h["a"] + h["b"];"#,
+            5,
+        ),
+        (r#"let h = {"a": [1]}; h["a"][0] = 7; h["a"][0];"#, 7),
+        // 赋值目标里的每个键只求值一次
+        (
+            "let c = 0; let f = fn() { c = c + 1; 0 }; let a = [[1, 2]]; a[f()][1] = 9; a[0][1] * 10 + c;",
+            91,
+        ),
+        // 键按从外到内的顺序求值
+        (
+            "let n = 0; let k = fn(d) { n = n * 10 + d; 0 }; let a = [[[0]]]; a[k(1)][k(2)][k(3)] = 5; n;",
+            123,
+        ),
+    ];
+
+    for (input, expected) in tests {
+        if !test_integer_object(test_eval(input)?, expected)? {
+            return Err(anyhow::anyhow!("wrong result for `{input}`"));
+        }
+    }
+
+    Ok(())
+}
+
+fn test_assign_errors() -> anyhow::Result<()> {
+    let tests = vec![
+        ("y = 1;", "identifier not found: `y`"),
+        ("let f = fn() { z = 1; }; f();", "identifier not found: `z`"),
+        (
+            "let arr = [1]; arr[3] = 1;",
+            "index out of range: index is `3` but length is `1`",
+        ),
+        (
+            "let x = 1; x[0] = 1;",
+            "index operator not supported: `INTEGER`",
+        ),
+    ];
+
+    for (input, expected) in tests {
+        match test_eval(input) {
+            Ok(value) => return Err(anyhow::anyhow!("expected error for `{input}`, got {value}")),
+            Err(error) if error.to_string() == expected => {}
+            Err(error) => {
+                return Err(anyhow::anyhow!(
+                    "wrong error for `{input}`. expected = {expected}, got = {error}"
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn test_loops() -> anyhow::Result<()> {
+    let tests = vec![
+        ("let i = 0; let sum = 0; while (i < 5) { i = i + 1; sum = sum + i; } sum;", 15),
+        ("let sum = 0; for (x in [1, 2, 3]) { sum = sum + x; } sum;", 6),
+        (r#"let n = 0; for (k in {"a": 1, "b": 2}) { n = n + 1; } n;"#, 2),
+        (r#"let n = 0; for (c in "héllo") { n = n + 1; } n;"#, 5),
+        (
+            "let i = 0; while (true) { i = i + 1; if (i == 3) { break; } } i;",
+            3,
+        ),
+        (
+            "let sum = 0; for (x in [1, 2, 3, 4]) { if (x == 2) { continue; } sum = sum + x; } sum;",
+            8,
+        ),
+        (
+            r#"
+let count = 0;
+for (i in [1, 2, 3]) {
+    for (j in [1, 2, 3]) {
+        if (j == 2) { break; }
+        count = count + 1;
+    }
+}
+count;"#,
+            3,
+        ),
+        (
+            "let find = fn(arr) { for (x in arr) { if (x > 1) { return x; } } 0 }; find([1, 5, 7]);",
+            5,
+        ),
+        (
+            "let fs = []; for (x in [1, 2]) { fs = push(fs, fn() { x }); } fs[0]() + fs[1]() * 10;",
+            21,
+        ),
+        ("let x = 7; for (x in [1, 2]) { } x;", 7),
+    ];
+
+    for (input, expected) in tests {
+        if !test_integer_object(test_eval(input)?, expected)? {
+            return Err(anyhow::anyhow!("wrong result for `{input}`"));
+        }
+    }
+
+    let tests = vec![
+        ("break;", "`break` outside of a loop"),
+        (
+            "let f = fn() { continue; }; while (true) { f(); }",
+            "`continue` outside of a loop",
+        ),
+        ("for (x in 5) { }", "`INTEGER` is not iterable"),
+    ];
+
+    for (input, expected) in tests {
+        match test_eval(input) {
+            Ok(value) => return Err(anyhow::anyhow!("expected error for `{input}`, got {value}")),
+            Err(error) if error.to_string() == expected => {}
+            Err(error) => {
+                return Err(anyhow::anyhow!(
+                    "wrong error for `{input}`. expected = {expected}, got = {error}"
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn test_checked_integer_arithmetic() -> anyhow::Result<()> {
+    let mut tests = vec![
+        ("1 / 0", "division by zero: `1 / 0`"),
+        ("let x = 0; 10 / x", "division by zero: `10 / 0`"),
+    ];
+    // 开启 bigint 时溢出会提升为大整数，而不是报错
+    #[cfg(feature = "bigint")]
+    tests.push((
+        "(9223372036854775807 + 1) / 0",
+        "division by zero: `9223372036854775808 / 0`",
+    ));
+    #[cfg(not(feature = "bigint"))]
+    tests.extend([
+        (
+            "9223372036854775807 + 1",
+            "integer overflow: `9223372036854775807 + 1`",
+        ),
+        (
+            "-9223372036854775807 - 2",
+            "integer overflow: `-9223372036854775807 - 2`",
+        ),
+        (
+            "4611686018427387904 * 2",
+            "integer overflow: `4611686018427387904 * 2`",
+        ),
+        (
+            "let min = -9223372036854775807 - 1; min / -1",
+            "integer overflow: `-9223372036854775808 / -1`",
+        ),
+        (
+            "let min = -9223372036854775807 - 1; -min",
+            "integer overflow: `0 - -9223372036854775808`",
+        ),
+        (
+            "9223372036854775808",
+            "integer literal `9223372036854775808` is out of range",
+        ),
+    ]);
+
+    for (input, expected) in tests {
+        match test_eval(input) {
+            Ok(value) => return Err(anyhow::anyhow!("expected error for {input}, got {value}")),
+            Err(error) if error.to_string() != expected => {
+                return Err(anyhow::anyhow!(
+                    "wrong error for {input}. expected = {expected}, got = {error}"
+                ))
+            }
+            Err(_) => {}
+        }
+    }
+
+    let evaluated = test_eval("9223372036854775806 + 1")?;
+    if !test_integer_object(evaluated, 9223372036854775807)? {
+        return Err(anyhow::anyhow!("largest integer is not reachable"));
+    }
+
+    let evaluated = test_eval("[][0]")?;
+    if evaluated != Null.into() {
+        return Err(anyhow::anyhow!(
+            "index into empty array should be null, got {evaluated}"
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "bigint")]
+fn test_bigint_promotion() -> anyhow::Result<()> {
+    let tests = vec![
+        ("9223372036854775807 + 1", "9223372036854775808"),
+        ("-9223372036854775807 - 2", "-9223372036854775809"),
+        ("4611686018427387904 * 4", "18446744073709551616"),
+        (
+            "let min = -9223372036854775807 - 1; -min",
+            "9223372036854775808",
+        ),
+        ("99999999999999999999 / 3", "33333333333333333333"),
+        (
+            "let f = 1; let i = 1; while (i < 26) { f = f * i; i = i + 1; } f",
+            "15511210043330985984000000",
+        ),
+        ("(9223372036854775807 + 1) - 1", "9223372036854775807"),
+        ("9223372036854775808 > 9223372036854775807", "true"),
+        ("-9223372036854775809 < 1", "true"),
+        ("9223372036854775808 == 9223372036854775807 + 1", "true"),
+        (
+            r#"let h = {9223372036854775808: "big", 1: "one"}; h[9223372036854775807 + 1]"#,
+            "big",
+        ),
+        (
+            r#"{1: "one"}[(9223372036854775807 + 2) - 9223372036854775808]"#,
+            "one",
+        ),
+        ("[1, 2][9223372036854775808]", "null"),
+        (
+            "quote(9223372036854775808 + 1)",
+            "QUOTE((9223372036854775808 + 1))",
+        ),
+    ];
+
+    for (input, expected) in tests {
+        let evaluated = test_eval(input)?;
+        if evaluated.to_string() != expected {
+            return Err(anyhow::anyhow!(
+                "wrong result for {input}. expected = {expected}, got = {evaluated}"
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn test_float_expressions() -> anyhow::Result<()> {
+    let tests = vec![
+        ("3.14", "3.14"),
+        ("1e-9", "1e-9"),
+        ("3.0", "3.0"),
+        ("-2.5", "-2.5"),
+        ("0.1 + 0.2", "0.30000000000000004"),
+        ("1 + 0.5", "1.5"),
+        ("0.5 * 4", "2.0"),
+        ("7 / 2.0", "3.5"),
+        ("7 / 2", "3"),
+        ("1.0 / 0", "inf"),
+        ("-1 / 0.0", "-inf"),
+        ("0.0 / 0.0", "NaN"),
+        ("let nan = 0.0 / 0.0; nan == nan", "false"),
+        ("let nan = 0.0 / 0.0; nan != nan", "true"),
+        ("1 == 1.0", "true"),
+        ("2.5 > 2", "true"),
+        ("1 < 0.5", "false"),
+        ("!0.0", "true"),
+        ("!1.5", "false"),
+        ("int(3.99)", "3"),
+        ("int(-3.99)", "-3"),
+        (r#"int(" 42 ")"#, "42"),
+        ("float(2)", "2.0"),
+        (r#"float("1e3")"#, "1000.0"),
+        ("floor(-1.5)", "-2"),
+        ("ceil(1.2)", "2"),
+        ("round(2.5)", "3"),
+        ("round(-2.5)", "-3"),
+        ("floor(7)", "7"),
+        ("sqrt(16)", "4.0"),
+        ("sqrt(2.25)", "1.5"),
+        ("sqrt(-1)", "NaN"),
+        ("quote(1.5 + 2)", "QUOTE((1.5 + 2))"),
+    ];
+
+    for (input, expected) in tests {
+        let evaluated = test_eval(input)?;
+        if evaluated.to_string() != expected {
+            return Err(anyhow::anyhow!(
+                "wrong result for {input}. expected = {expected}, got = {evaluated}"
+            ));
+        }
+    }
+
+    let errors = vec![
+        ("int(0.0 / 0.0)", "cannot convert `NaN` to INTEGER"),
+        ("floor(1.0 / 0)", "cannot convert `inf` to INTEGER"),
+        (r#"int("1.5")"#, "cannot convert `1.5` to INTEGER"),
+        (r#"float("abc")"#, "cannot convert `abc` to FLOAT"),
+        (
+            "sqrt(true)",
+            "argument to `sqrt` not supported, got `BOOLEAN`",
+        ),
+        ("1.5 + true", "type mismatch: FLOAT + BOOLEAN"),
+        ("{0.0 / 0.0: 1}", "unusable as hash key: `NaN`"),
+    ];
+
+    for (input, expected) in errors {
+        match test_eval(input) {
+            Ok(value) => return Err(anyhow::anyhow!("expected error for {input}, got {value}")),
+            Err(error) if error.to_string() != expected => {
+                return Err(anyhow::anyhow!(
+                    "wrong error for {input}. expected = {expected}, got = {error}"
+                ))
+            }
+            Err(_) => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn test_float_hash_keys() -> anyhow::Result<()> {
+    let tests = vec![
+        (r#"{1: "one"}[1.0]"#, "one"),
+        (r#"{1.0: "one"}[1]"#, "one"),
+        (r#"{0.5: "half"}[1 / 2.0]"#, "half"),
+        (r#"{0.0: "zero"}[-0.0]"#, "zero"),
+        (r#"{1.5: "a"}[1.25]"#, "null"),
+        (r#"let h = {}; h[2.0] = "two"; h[2]"#, "two"),
+    ];
+
+    for (input, expected) in tests {
+        let evaluated = test_eval(input)?;
+        if evaluated.to_string() != expected {
+            return Err(anyhow::anyhow!(
+                "wrong result for {input}. expected = {expected}, got = {evaluated}"
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn test_unicode_strings() -> anyhow::Result<()> {
+    let tests = vec![
+        (r#"len("héllo")"#, "5"),
+        (r#"len("你好，世界")"#, "5"),
+        (r#"len("😀👍")"#, "2"),
+        // 肤色修饰符是单独的 Unicode 标量值
+        (r#"len("👍🏽")"#, "2"),
+        (r#"len("")"#, "0"),
+        (r#""你好世界"[1]"#, "好"),
+        (r#""a😀b"[1]"#, "😀"),
+        (r#""a😀b"[2]"#, "b"),
+        (r#""héllo"[5]"#, "null"),
+        (r#""héllo"[-1]"#, "null"),
+        (r#"slice("你好，世界", 3)"#, "世界"),
+        (r#"slice("héllo wörld", 1, 4)"#, "éll"),
+        (r#"slice("😀😁😂", 1, 2)"#, "😁"),
+        (r#"slice("abc", -5, 100)"#, "abc"),
+        (r#"slice("abc", 2, 1)"#, ""),
+        ("slice([1, 2, 3, 4], 1, 3)", "[2,3]"),
+        ("slice([1, 2, 3], 5)", "[]"),
+        (r#"let 名前 = "世界"; "你好，" + 名前"#, "你好，世界"),
+        (
+            r#"let 计数 = 0; for (字 in "日本語") { 计数 = 计数 + 1; } 计数"#,
+            "3",
+        ),
+        (r#"let h = {"キー": "値"}; h["キー"]"#, "値"),
+    ];
+
+    for (input, expected) in tests {
+        let evaluated = test_eval(input)?;
+        if evaluated.to_string() != expected {
+            return Err(anyhow::anyhow!(
+                "wrong result for {input}. expected = {expected}, got = {evaluated}"
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn test_string_interpolation() -> anyhow::Result<()> {
+    let tests = vec![
+        (r#"let name = "Kirby"; "hello ${name}!""#, "hello Kirby!"),
+        (
+            r#""${1 + 2} ${1.5 * 2.0} ${true} ${[1, 2]}""#,
+            "3 3.0 true [1,2]",
+        ),
+        (r#"let f = fn(x) { x * 2 }; "${f(2)}${f(3)}""#, "46"),
+        (r#""a ${ "x}" + "[${ {"k": "v"}["k"] }]" } b""#, "a x}[v] b"),
+        (r#""\"${"q"}\" \${not}""#, "\"q\" ${not}"),
+        ("\"\"\"${1}\n${2}\"\"\"", "1\n2"),
+    ];
+
+    for (input, expected) in tests {
+        let evaluated = test_eval(input)?;
+        let string = StringObj::try_from(evaluated)?;
+        if string.value() != expected {
+            return Err(anyhow::anyhow!(
+                "interpolation wrong for {input}. expected = {expected:?}, got = {:?}",
+                string.value()
+            ));
+        }
+    }
+
+    // 插值表达式中的错误会原样传出
+    let error = test_eval(r#""${missing}""#).unwrap_err();
+    if error.to_string() != "identifier not found: `missing`" {
+        return Err(anyhow::anyhow!("wrong error message. got = {error}"));
+    }
+
+    Ok(())
+}
+
+fn test_number_literal_forms() -> anyhow::Result<()> {
+    let tests = vec![
+        ("1_000_000", "1000000"),
+        ("0xFF + 1", "256"),
+        ("0xdead_beef", "3735928559"),
+        ("0o17", "15"),
+        ("0b1010_0101", "165"),
+        ("-0x10", "-16"),
+        ("0x7fff_ffff_ffff_ffff", "9223372036854775807"),
+        ("1_000.5", "1000.5"),
+        ("2e1_0", "20000000000.0"),
+        (
+            "let snake_case_name = 1; let _unused = 2; snake_case_name + _unused",
+            "3",
+        ),
+    ];
+
+    for (input, expected) in tests {
+        let evaluated = test_eval(input)?;
+        if evaluated.to_string() != expected {
+            return Err(anyhow::anyhow!(
+                "wrong value for {input}. expected = {expected}, got = {}",
+                evaluated.to_string()
+            ));
+        }
+    }
+
+    // 超出 isize 的十六进制字面量和十进制一样提升为大整数
+    #[cfg(feature = "bigint")]
+    {
+        let evaluated = test_eval("0x1_0000_0000_0000_0000")?;
+        if evaluated.to_string() != "18446744073709551616" {
+            return Err(anyhow::anyhow!("wrong big hex literal. got = {evaluated}"));
+        }
+    }
+
+    Ok(())
+}
+
+fn test_extended_operators() -> anyhow::Result<()> {
+    let tests = vec![
+        ("3 <= 3", "true"),
+        ("4 <= 3", "false"),
+        ("3 >= 4", "false"),
+        ("2.5 >= 2", "true"),
+        (r#""apple" < "banana""#, "true"),
+        (r#""b" >= "abc""#, "true"),
+        ("7 % 3", "1"),
+        ("-7 % 3", "-1"),
+        ("7.5 % 2", "1.5"),
+        ("2 ** 10", "1024"),
+        ("2 ** 3 ** 2", "512"),
+        ("-2 ** 2", "-4"),
+        ("(-2) ** 3", "-8"),
+        ("2 ** -1", "0.5"),
+        ("2.0 ** 0.5 == sqrt(2.0)", "true"),
+        ("6 & 3", "2"),
+        ("6 | 3", "7"),
+        ("6 ^ 3", "5"),
+        ("~5", "-6"),
+        ("-1 & 0xFF", "255"),
+        ("1 << 4", "16"),
+        ("-16 >> 2", "-4"),
+        ("-1 >> 100", "-1"),
+        ("5 >> 100", "0"),
+        ("true && false", "false"),
+        ("true || false", "true"),
+        ("false || 1 > 0", "true"),
+        ("false || false", "false"),
+        ("1 < 2 && 2 < 3", "true"),
+        // 短路：右侧不会被计算，否则会报告标识符未定义或者除零
+        ("false && missing", "false"),
+        ("true || 1 / 0", "true"),
+        (
+            "let n = 0; let bump = fn() { n = n + 1; true }; false && bump(); true || bump(); n",
+            "0",
+        ),
+    ];
+
+    for (input, expected) in tests {
+        let evaluated = test_eval(input)?;
+        if evaluated.to_string() != expected {
+            return Err(anyhow::anyhow!(
+                "wrong value for {input}. expected = {expected}, got = {evaluated}"
+            ));
+        }
+    }
+
+    let errors = vec![
+        ("1 % 0", "division by zero: `1 % 0`"),
+        ("1 << -1", "negative shift amount: `1 << -1`"),
+        ("~1.5", "unknown operator: ~FLOAT"),
+        ("1.5 & 1", "unknown operator: FLOAT & INTEGER"),
+        ("5 & 1.0", "unknown operator: INTEGER & FLOAT"),
+        ("1 << 2.0", "unknown operator: INTEGER << FLOAT"),
+        ("1.5 | 2.5", "unknown operator: FLOAT | FLOAT"),
+        (r#""a" % "b""#, "unknown operator: STRING % STRING"),
+        ("true && missing", "identifier not found: `missing`"),
+        #[cfg(not(feature = "bigint"))]
+        ("1 << 63", "integer overflow: `1 << 63`"),
+        #[cfg(not(feature = "bigint"))]
+        ("2 ** 63", "integer overflow: `2 ** 63`"),
+    ];
+
+    for (input, expected) in errors {
+        match test_eval(input) {
+            Ok(value) => return Err(anyhow::anyhow!("expected error for {input}, got = {value}")),
+            Err(error) if error.to_string() != expected => {
+                return Err(anyhow::anyhow!(
+                    "wrong error for {input}. expected = {expected}, got = {error}"
+                ))
+            }
+            Err(_) => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "bigint")]
+fn test_extended_operators_bigint() -> anyhow::Result<()> {
+    let tests = vec![
+        ("2 ** 64", "18446744073709551616"),
+        ("1 << 70", "1180591620717411303424"),
+        ("(1 << 70) >> 69", "2"),
+        ("(2 ** 64 + 5) % 2 ** 64", "5"),
+        ("(2 ** 64) | 1", "18446744073709551617"),
+        ("~(2 ** 64)", "-18446744073709551617"),
+        ("-(2 ** 64) >> 1", "-9223372036854775808"),
+        ("1 ** 4000000000", "1"),
+        ("(-1) ** 4000000001", "-1"),
+        ("0 << 4000000000", "0"),
+        ("(1 << 4194303) >> 4194303", "1"),
+    ];
+
+    for (input, expected) in tests {
+        let evaluated = test_eval(input)?;
+        if evaluated.to_string() != expected {
+            return Err(anyhow::anyhow!(
+                "wrong value for {input}. expected = {expected}, got = {evaluated}"
+            ));
+        }
+    }
+
+    // 结果太大时报溢出，而不是一直计算下去
+    for input in [
+        "2 ** 4000000000",
+        "(2 ** 64) ** 100000",
+        "1 << 4000000000",
+        "1 << 4194304",
+        "(2 ** 64) << 4194300",
+    ] {
+        match test_eval(input) {
+            Err(error) if matches!(error.downcast_ref(), Some(Error::IntegerOverflow { .. })) => {}
+            result => {
+                return Err(anyhow::anyhow!(
+                    "expected overflow for {input}, got {result:?}"
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn test_exit_builtin() -> anyhow::Result<()> {
+    let tests = vec![
+        ("exit()", Some(0)),
+        ("exit(3); 1", Some(3)),
+        ("let f = fn() { exit(255); 2 }; f(); 3", Some(255)),
+        ("if (false) { exit(1) }; 4", None),
+    ];
+
+    for (input, expected) in tests {
+        let code = match test_eval(input) {
+            Ok(_) => None,
+            Err(error) => Some(
+                runner::exit_code(&error)
+                    .ok_or_else(|| anyhow::anyhow!("unexpected error for {input}: {error}"))?,
+            ),
+        };
+        if code != expected {
+            return Err(anyhow::anyhow!(
+                "exit code wrong for {input}. expected = {expected:?}, got = {code:?}"
+            ));
+        }
+    }
+
+    let errors = vec![
+        (
+            r#"exit("1")"#,
+            "argument to `exit` not supported, got `STRING`",
+        ),
+        ("exit(1, 2)", "wrong number of arguments. got=`2`, want=`1`"),
+        (
+            "exit(2 ** 40)",
+            "cannot convert `1099511627776` to exit code",
+        ),
+        ("exit(-1)", "cannot convert `-1` to exit code"),
+        ("exit(256)", "cannot convert `256` to exit code"),
+    ];
+
+    for (input, expected) in errors {
+        match test_eval(input) {
+            Err(error) if error.to_string() == expected => {}
+            other => {
+                return Err(anyhow::anyhow!(
+                    "wrong result for {input}. expected = {expected}, got = {other:?}"
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn test_script_args() -> anyhow::Result<()> {
+    let args = vec!["one".to_string(), "--two".to_string()];
+    let mut env = runner::new_environment(&args);
+    let evaluated = runner::eval_source(
+        r#""${len(args)} " + args[0] + args[1]"#,
+        &mut env,
+        &mut Environment::new(),
+    )?;
+    if evaluated.to_string() != "2 one--two" {
+        return Err(anyhow::anyhow!("wrong args. got = {evaluated}"));
+    }
+
+    let evaluated = runner::eval_source(
+        "args",
+        &mut runner::new_environment(&[]),
+        &mut Environment::new(),
+    )?;
+    if evaluated.to_string() != "[]" {
+        return Err(anyhow::anyhow!("args should be empty. got = {evaluated}"));
+    }
+
+    Ok(())
+}
+
 fn test_string_literal() -> anyhow::Result<()> {
     let input = r#""Hello World!""#;
     let evaluated = test_eval(input)?;
@@ -768,6 +1632,30 @@ fn test_quote_unquote() -> anyhow::Result<()> {
             input: "quote(unquote(4 + 4) + 8)",
             expected: "(8 + 8)",
         },
+        Test {
+            input: "let foobar = 8; quote(foobar)",
+            expected: "foobar",
+        },
+        Test {
+            input: "let foobar = 8; quote(unquote(foobar))",
+            expected: "8",
+        },
+        Test {
+            input: "quote(unquote(true))",
+            expected: "true",
+        },
+        Test {
+            input: "quote(unquote(true == false))",
+            expected: "false",
+        },
+        Test {
+            input: "quote(unquote(quote(4 + 4)))",
+            expected: "(4 + 4)",
+        },
+        Test {
+            input: "let quotedInfixExpression = quote(4 + 4); quote(unquote(4 + 4) + unquote(quotedInfixExpression))",
+            expected: "(8 + (4 + 4))",
+        },
     ];
 
     for tt in tests {
@@ -786,6 +1674,130 @@ fn test_quote_unquote() -> anyhow::Result<()> {
     Ok(())
 }
 
+fn test_parse_program(input: &str) -> anyhow::Result<Program> {
+    let lexer = lexer(input).unwrap().1;
+    let mut parser = Parser::new(lexer)?;
+    parser.parse_program()
+}
+
+fn test_define_macros() -> anyhow::Result<()> {
+    let input = r#"
+let number = 1;
+let function = fn(x, y) { x + y };
+let mymacro = macro(x, y) { x + y; };
+"#;
+
+    let mut env = Environment::new();
+    let mut program = test_parse_program(input)?;
+
+    program.define_macros(&mut env);
+
+    if program.len() != 2 {
+        return Err(anyhow::anyhow!(
+            "wrong number of statements. got={}",
+            program.len()
+        ));
+    }
+
+    if env.get("number".into()).is_some() {
+        return Err(anyhow::anyhow!("number should not be defined"));
+    }
+    if env.get("function".into()).is_some() {
+        return Err(anyhow::anyhow!("function should not be defined"));
+    }
+
+    let obj = env
+        .get("mymacro".into())
+        .ok_or_else(|| anyhow::anyhow!("macro not in environment."))?;
+    let macro_object = Macro::try_from(obj.clone())?;
+
+    if macro_object.parameters().len() != 2 {
+        return Err(anyhow::anyhow!(
+            "Wrong number of macro parameters. got={}",
+            macro_object.parameters().len()
+        ));
+    }
+    if macro_object.parameters()[0].to_string() != "x" {
+        return Err(anyhow::anyhow!(
+            "parameter is not 'x'. got={}",
+            macro_object.parameters()[0]
+        ));
+    }
+    if macro_object.parameters()[1].to_string() != "y" {
+        return Err(anyhow::anyhow!(
+            "parameter is not 'y'. got={}",
+            macro_object.parameters()[1]
+        ));
+    }
+    if macro_object.body().to_string() != "(x + y)" {
+        return Err(anyhow::anyhow!(
+            "body is not (x + y). got={}",
+            macro_object.body()
+        ));
+    }
+
+    Ok(())
+}
+
+fn test_expand_macros() -> anyhow::Result<()> {
+    struct Test<'a> {
+        input: &'a str,
+        expected: &'a str,
+    }
+
+    let tests = vec![
+        Test {
+            input: r#"
+let infixExpression = macro() { quote(1 + 2); };
+
+infixExpression();
+"#,
+            expected: "(1 + 2)",
+        },
+        Test {
+            input: r#"
+let reverse = macro(a, b) { quote(unquote(b) - unquote(a)); };
+
+reverse(2 + 2, 10 - 5);
+"#,
+            expected: "(10 - 5) - (2 + 2)",
+        },
+        Test {
+            input: r#"
+let unless = macro(condition, consequence, alternative) {
+    quote(if (!(unquote(condition))) {
+        unquote(consequence);
+    } else {
+        unquote(alternative);
+    });
+};
+
+unless(10 > 5, puts("not greater"), puts("greater"));
+"#,
+            expected: r#"if (!(10 > 5)) { puts("not greater") } else { puts("greater") }"#,
+        },
+    ];
+
+    for tt in tests {
+        let expected = test_parse_program(tt.expected)?;
+        let mut program = test_parse_program(tt.input)?;
+
+        let mut env = Environment::new();
+        program.define_macros(&mut env);
+        let expanded = program.expand_macros(&mut env)?;
+
+        if expanded.to_string() != expected.to_string() {
+            return Err(anyhow::anyhow!(
+                "not equal. want={}, got={}",
+                expected,
+                expanded
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 #[derive(Debug)]
 pub enum Interface {
     Isize(isize),
@@ -878,6 +1890,7 @@ fn test_test_return_statements() {
 fn test_test_error_handling() {
     let ret = test_error_handling();
     println!("test_error_handling: ret = {ret:?}");
+    assert!(ret.is_ok());
 }
 
 #[test]
@@ -964,8 +1977,258 @@ fn test_test_quote() {
     println!("test_quote: ret = {ret:?}");
 }
 
+fn test_error_span() -> anyhow::Result<()> {
+    struct Test {
+        input: &'static str,
+        expected: Span,
+    }
+
+    let tests = [
+        Test {
+            input: "let a = 1;\n  a + foobar;",
+            expected: Span::new(17, 23, 2, 7),
+        },
+        Test {
+            input: "let s = \"a\";\ns - s;",
+            expected: Span::new(13, 18, 2, 1),
+        },
+        Test {
+            input: "1;\n-true;",
+            expected: Span::new(3, 8, 2, 1),
+        },
+        Test {
+            input: "1 + true;",
+            expected: Span::new(0, 8, 1, 1),
+        },
+        Test {
+            input: "exit(256);",
+            expected: Span::new(0, 8, 1, 1),
+        },
+        Test {
+            input: "len(1, 2);",
+            expected: Span::new(0, 8, 1, 1),
+        },
+        Test {
+            input: "int(\"x\");",
+            expected: Span::new(0, 7, 1, 1),
+        },
+        Test {
+            input: "let f = 1;\nf(2);",
+            expected: Span::new(11, 14, 2, 1),
+        },
+        Test {
+            input: "let f = fn() { f() };\nf();",
+            expected: Span::new(15, 17, 1, 16),
+        },
+        Test {
+            input: "let a = [1];\na[5] = 2;",
+            expected: Span::new(13, 16, 2, 1),
+        },
+        Test {
+            input: "let a = [1];\na[0][1];",
+            expected: Span::new(13, 19, 2, 1),
+        },
+        Test {
+            input: "let h = {0.0 / 0.0: 1};",
+            expected: Span::new(9, 18, 1, 10),
+        },
+        Test {
+            input: "for (x in 5) { x }",
+            expected: Span::new(10, 11, 1, 11),
+        },
+        Test {
+            input: "1;\nbreak;",
+            expected: Span::new(3, 8, 2, 1),
+        },
+        Test {
+            input: "let f = fn() { break; };\nf();",
+            expected: Span::new(25, 27, 2, 1),
+        },
+    ];
+
+    for tt in tests {
+        let error = match test_eval(tt.input) {
+            Ok(value) => return Err(anyhow::anyhow!("expected error, got {value}")),
+            Err(error) => error.downcast::<Error>()?,
+        };
+        if error.span() != Some(tt.expected) {
+            return Err(anyhow::anyhow!(
+                "error span wrong. expected = {:?}, got = {:?}",
+                tt.expected,
+                error.span()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_test_quote_unquote() {
     let ret = test_quote_unquote();
     println!("test_quote_unquote: ret = {ret:?}");
 }
+
+#[test]
+fn test_test_define_macros() {
+    let ret = test_define_macros();
+    println!("test_define_macros: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_expand_macros() {
+    let ret = test_expand_macros();
+    println!("test_expand_macros: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_error_span() {
+    let ret = test_error_span();
+    println!("test_error_span: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_recursive_functions() {
+    let ret = test_recursive_functions();
+    println!("test_recursive_functions: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_wrong_number_of_function_arguments() {
+    let ret = test_wrong_number_of_function_arguments();
+    println!("test_wrong_number_of_function_arguments: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_assign_expressions() {
+    let ret = test_assign_expressions();
+    println!("test_assign_expressions: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_assign_errors() {
+    let ret = test_assign_errors();
+    println!("test_assign_errors: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_loops() {
+    let ret = test_loops();
+    println!("test_loops: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_equality_between_types() {
+    let ret = test_equality_between_types();
+    println!("test_equality_between_types: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_checked_integer_arithmetic() {
+    let ret = test_checked_integer_arithmetic();
+    println!("test_checked_integer_arithmetic: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[cfg(feature = "bigint")]
+#[test]
+fn test_test_bigint_promotion() {
+    let ret = test_bigint_promotion();
+    println!("test_bigint_promotion: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_float_expressions() {
+    let ret = test_float_expressions();
+    println!("test_float_expressions: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_float_hash_keys() {
+    let ret = test_float_hash_keys();
+    println!("test_float_hash_keys: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_unicode_strings() {
+    let ret = test_unicode_strings();
+    println!("test_unicode_strings: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_string_interpolation() {
+    let ret = test_string_interpolation();
+    println!("test_string_interpolation: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_number_literal_forms() {
+    let ret = test_number_literal_forms();
+    println!("test_number_literal_forms: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_extended_operators() {
+    let ret = test_extended_operators();
+    println!("test_extended_operators: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[cfg(feature = "bigint")]
+#[test]
+fn test_test_extended_operators_bigint() {
+    let ret = test_extended_operators_bigint();
+    println!("test_extended_operators_bigint: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_exit_builtin() {
+    let ret = test_exit_builtin();
+    println!("test_exit_builtin: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_script_args() {
+    let ret = test_script_args();
+    println!("test_script_args: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_named_functions_release_environment() {
+    let ret = test_named_functions_release_environment();
+    println!("test_named_functions_release_environment: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_nesting_depth_limit() {
+    let ret = test_nesting_depth_limit();
+    println!("test_nesting_depth_limit: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_call_depth_limit() {
+    // 默认的测试线程栈很小，深递归依靠求值时按需分配的栈段
+    let ret = test_call_depth_limit();
+    println!("test_call_depth_limit: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
@@ -1,86 +1,543 @@
+use crate::error::Error;
+use crate::token::span::Span;
 use crate::token::token_type::TokenType;
 use crate::token::{token_type, Token};
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::bytes::complete::take_till;
-use nom::sequence::terminated;
-use nom::{character::complete::*, combinator::*, multi::*, IResult};
+use nom::bytes::complete::{take_till, take_while};
+use nom::error::{ErrorKind, ParseError};
+use nom::multi::many0_count;
+use nom::sequence::{pair, tuple};
+use nom::{character::complete::*, combinator::*, IResult};
 use std::str;
+use unicode_xid::UnicodeXID;
 
 #[cfg(test)]
 mod tests;
 
-fn parse_identifier(input: &str) -> IResult<&str, Token> {
-    let (input, ident) = alphanumeric1(input)?;
+/// 标识符遵循 Unicode XID 规则：以 XID_Start 或 `_` 开头，后面跟任意个 XID_Continue
+fn parse_identifier(input: &str) -> LexResult<'_, Token> {
+    let (input, ident) = recognize(pair(
+        satisfy(|c| c == '_' || c.is_xid_start()),
+        take_while(UnicodeXID::is_xid_continue),
+    ))(input)?;
     let token_type = token_type::lookup_ident(ident);
     Ok((input, Token::from_string(token_type, ident.to_string())))
 }
 
-fn parse_double_char_operators(input: &str) -> IResult<&str, Token> {
+fn parse_double_char_operators(input: &str) -> LexResult<'_, Token> {
+    let operator = |literal: &'static str, token_type: TokenType| {
+        map(tag(literal), move |_| {
+            Token::from_string(token_type.clone(), literal.to_string())
+        })
+    };
     alt((
-        map(tag("=="), |_| {
-            Token::from_string(TokenType::EQ, "==".to_string())
-        }),
-        map(tag("!="), |_| {
-            Token::from_string(TokenType::NOTEQ, "!=".to_string())
-        }),
+        operator("==", TokenType::EQ),
+        operator("!=", TokenType::NOTEQ),
+        operator("<=", TokenType::LTEQ),
+        operator(">=", TokenType::GTEQ),
+        operator("&&", TokenType::AND),
+        operator("||", TokenType::OR),
+        operator("<<", TokenType::SHL),
+        operator(">>", TokenType::SHR),
+        operator("**", TokenType::POWER),
     ))(input)
 }
 
 // 解析单字符运算符和其他单字符语法元素
-fn parse_single_char_tokens(input: &str) -> IResult<&str, Token> {
-    alt((
-        map(tag("+"), |_| Token::new(token_type::lookup_char('+'), '+')),
-        map(tag("-"), |_| Token::new(token_type::lookup_char('-'), '-')),
-        map(tag("/"), |_| Token::new(token_type::lookup_char('/'), '/')),
-        map(tag("*"), |_| Token::new(token_type::lookup_char('*'), '*')),
-        map(tag("<"), |_| Token::new(token_type::lookup_char('<'), '<')),
-        map(tag(">"), |_| Token::new(token_type::lookup_char('>'), '>')),
-        map(tag("["), |_| Token::new(token_type::lookup_char('['), '[')),
-        map(tag("]"), |_| Token::new(token_type::lookup_char(']'), ']')),
-        map(tag(":"), |_| Token::new(token_type::lookup_char(':'), ':')),
-        map(tag("="), |_| Token::new(token_type::lookup_char('='), '=')),
-        map(tag("!"), |_| Token::new(token_type::lookup_char('!'), '!')),
-        map(tag(";"), |_| Token::new(token_type::lookup_char(';'), ';')),
-        map(tag("("), |_| Token::new(token_type::lookup_char('('), '(')),
-        map(tag(")"), |_| Token::new(token_type::lookup_char(')'), ')')),
-        map(tag(","), |_| Token::new(token_type::lookup_char(','), ',')),
-        map(tag("{"), |_| Token::new(token_type::lookup_char('{'), '{')),
-        map(tag("}"), |_| Token::new(token_type::lookup_char('}'), '}')),
-    ))(input)
+fn parse_single_char_tokens(input: &str) -> LexResult<'_, Token> {
+    map(one_of("+-/*<>[]:=!;(),{}%&|^~"), |ch| {
+        Token::new(token_type::lookup_char(ch), ch)
+    })(input)
+}
+
+const TRIPLE_QUOTE: &str = r#"""""#;
+
+/// 字符串字面量，依次尝试三引号字符串、原始字符串和普通字符串：
+/// - `"""..."""` 可以跨行，可以直接包含 `"`，支持转义和插值
+/// - `r"..."` 不处理转义和插值，遇到第一个 `"` 结束
+/// - `"..."` 支持 `\n \t \r \0 \\ \" \$ \u{1F600}` 转义和 `${expr}` 插值
+///
+/// 含有插值的字符串在第一个 `${` 处结束，得到 STRINGSTART，
+/// 剩下的部分由 `lexer` 在插值表达式结束后继续扫描
+fn parse_string(input: &str) -> LexResult<'_, Token> {
+    let (terminator, body) = if let Some(body) = input.strip_prefix(TRIPLE_QUOTE) {
+        (TRIPLE_QUOTE, body)
+    } else if let Some(body) = input.strip_prefix(r#"r""#) {
+        let (rest, value) = raw_string_body(input, body)?;
+        return Ok((rest, Token::from_string(TokenType::STRING, value)));
+    } else if let Some(body) = input.strip_prefix('"') {
+        ("\"", body)
+    } else {
+        return Err(nom::Err::Error(LexError::from_error_kind(
+            input,
+            ErrorKind::Char,
+        )));
+    };
+
+    let (rest, (value, end)) = string_segment(input, body, terminator)?;
+    let token_type = match end {
+        SegmentEnd::Terminator => TokenType::STRING,
+        SegmentEnd::Interpolation => TokenType::STRINGSTART,
+    };
+    Ok((rest, Token::from_string(token_type, value)))
+}
+
+/// 原始字符串的内容，直到下一个 `"`
+fn raw_string_body<'a>(start: &'a str, body: &'a str) -> LexResult<'a, String> {
+    match body.find('"') {
+        Some(end) => Ok((&body[end + 1..], body[..end].to_string())),
+        None => Err(LexError::failure(
+            start,
+            start.len() - body.len(),
+            LexErrorKind::UnterminatedString,
+        )),
+    }
+}
+
+/// 字符串片段在哪里结束
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SegmentEnd {
+    /// 遇到了结束引号
+    Terminator,
+    /// 遇到了 `${`，后面是插值表达式
+    Interpolation,
+}
+
+/// 读取字符串片段直到 `terminator` 或者 `${`，同时处理转义。
+/// `start` 是开头引号的位置，用于报告未闭合的字符串
+fn string_segment<'a>(
+    start: &'a str,
+    body: &'a str,
+    terminator: &str,
+) -> LexResult<'a, (String, SegmentEnd)> {
+    let mut value = String::new();
+    let mut rest = body;
+    loop {
+        if let Some(remaining) = rest.strip_prefix(terminator) {
+            return Ok((remaining, (value, SegmentEnd::Terminator)));
+        }
+        if let Some(remaining) = rest.strip_prefix("${") {
+            return Ok((remaining, (value, SegmentEnd::Interpolation)));
+        }
+        let mut chars = rest.chars();
+        match chars.next() {
+            None => {
+                return Err(LexError::failure(
+                    start,
+                    start.len() - body.len(),
+                    LexErrorKind::UnterminatedString,
+                ));
+            }
+            Some('\\') => {
+                let (remaining, ch) = parse_escape(rest)?;
+                value.push(ch);
+                rest = remaining;
+            }
+            Some(ch) => {
+                value.push(ch);
+                rest = chars.as_str();
+            }
+        }
+    }
 }
 
-fn parse_string(input: &str) -> IResult<&str, Token> {
-    let (input, _) = char('"')(input)?;
-    let (input, str_contents) = take_till(|c| c == '"')(input)?;
-    let (input, _) = char('"')(input)?;
-    Ok((
-        input,
-        Token::from_string(TokenType::STRING, str_contents.to_string()),
+/// 解析以 `\` 开头的一个转义序列
+fn parse_escape(input: &str) -> LexResult<'_, char> {
+    let body = &input[1..];
+    let mut chars = body.chars();
+    let ch = match chars.next() {
+        Some('n') => '\n',
+        Some('t') => '\t',
+        Some('r') => '\r',
+        Some('0') => '\0',
+        Some('\\') => '\\',
+        Some('"') => '"',
+        Some('$') => '$',
+        Some('u') => return parse_unicode_escape(input),
+        Some(ch) => {
+            let len = 1 + ch.len_utf8();
+            return Err(LexError::failure(
+                input,
+                len,
+                LexErrorKind::InvalidEscape(input[..len].to_string()),
+            ));
+        }
+        None => {
+            return Err(LexError::failure(
+                input,
+                1,
+                LexErrorKind::InvalidEscape("\\".into()),
+            ))
+        }
+    };
+    Ok((chars.as_str(), ch))
+}
+
+/// `\u{XXXX}`：1 到 6 位十六进制数，必须是合法的 Unicode 标量值
+fn parse_unicode_escape(input: &str) -> LexResult<'_, char> {
+    let escape: Option<(&str, char)> = (|| {
+        let digits = input.strip_prefix("\\u{")?;
+        let end = digits.find('}')?;
+        let hex = &digits[..end];
+        if hex.is_empty() || hex.len() > 6 {
+            return None;
+        }
+        let ch = char::from_u32(u32::from_str_radix(hex, 16).ok()?)?;
+        Some((&digits[end + 1..], ch))
+    })();
+
+    match escape {
+        Some(value) => Ok(value),
+        None => {
+            // 错误信息中包含到 `}` 为止的整个转义序列
+            let len = input
+                .find('}')
+                .filter(|end| !input[..*end].contains(['"', '\n']))
+                .map_or(2, |end| end + 1);
+            Err(LexError::failure(
+                input,
+                len,
+                LexErrorKind::InvalidEscape(input[..len].to_string()),
+            ))
+        }
+    }
+}
+
+/// 整数 `123`、`0xFF`、`0o17`、`0b1010`，或者带小数部分、指数部分的浮点数
+/// `3.14`、`1e-9`、`2.5E3`。相邻的数字之间可以有一个 `_`：`1_000_000`
+///
+/// 数字后面紧跟字母、数字或 `_` 时（`1abc`、`0b102`、`1_`），整体是一个错误的字面量，
+/// 而不是数字加标识符
+fn parse_number(input: &str) -> LexResult<'_, Token> {
+    let (rest, (num, token_type)) = alt((
+        map(
+            recognize(pair(tag("0x"), digits(|c| c.is_ascii_hexdigit()))),
+            |num| (num, TokenType::INT),
+        ),
+        map(
+            recognize(pair(tag("0o"), digits(|c| c.is_digit(8)))),
+            |num| (num, TokenType::INT),
+        ),
+        map(
+            recognize(pair(tag("0b"), digits(|c| c.is_digit(2)))),
+            |num| (num, TokenType::INT),
+        ),
+        map(
+            recognize(tuple((
+                digits(|c| c.is_ascii_digit()),
+                opt(pair(char('.'), digits(|c| c.is_ascii_digit()))),
+                opt(tuple((
+                    one_of("eE"),
+                    opt(one_of("+-")),
+                    digits(|c| c.is_ascii_digit()),
+                ))),
+            ))),
+            |num: &str| {
+                if num.contains(['.', 'e', 'E']) {
+                    (num, TokenType::FLOAT)
+                } else {
+                    (num, TokenType::INT)
+                }
+            },
+        ),
+    ))(input)?;
+
+    if rest.starts_with(UnicodeXID::is_xid_continue) {
+        let (_, tail) = take_while(UnicodeXID::is_xid_continue)(rest)?;
+        let len = num.len() + tail.len();
+        return Err(LexError::failure(
+            input,
+            len,
+            LexErrorKind::InvalidNumber(input[..len].to_string()),
+        ));
+    }
+    Ok((rest, Token::from_string(token_type, num.to_string())))
+}
+
+/// 一串数字，相邻的数字之间可以有一个 `_`
+fn digits<'a>(is_digit: fn(char) -> bool) -> impl FnMut(&'a str) -> LexResult<'a, &'a str> {
+    recognize(pair(
+        satisfy(is_digit),
+        many0_count(pair(opt(char('_')), satisfy(is_digit))),
     ))
 }
 
-fn parse_number(input: &str) -> IResult<&str, Token> {
-    let (input, num) = digit1(input)?;
-    Ok((input, Token::from_string(TokenType::INT, num.to_string())))
+/// 跳过空白和注释，返回其中的文档注释（`///` 开头的行，去掉 `///` 和一个空格）。
+/// `//` 注释到行尾，`/* */` 注释可以嵌套；`////` 是普通注释
+fn parse_trivia(input: &str) -> LexResult<'_, Vec<String>> {
+    let mut docs = vec![];
+    let mut rest = input;
+    loop {
+        let (remaining, _) = multispace0(rest)?;
+        rest = remaining;
+        if rest.starts_with("///") && !rest.starts_with("////") {
+            let (remaining, line) = line_comment(&rest[3..])?;
+            let line = line.trim_end_matches('\r');
+            docs.push(line.strip_prefix(' ').unwrap_or(line).to_string());
+            rest = remaining;
+        } else if rest.starts_with("//") {
+            let (remaining, _) = line_comment(rest)?;
+            rest = remaining;
+        } else if rest.starts_with("/*") {
+            let (remaining, _) = block_comment(rest)?;
+            rest = remaining;
+        } else {
+            return Ok((rest, docs));
+        }
+    }
+}
+
+/// 行注释，读到换行符之前
+fn line_comment(input: &str) -> LexResult<'_, &str> {
+    take_till(|c| c == '\n')(input)
 }
 
-fn parse_whitespace(input: &str) -> IResult<&str, ()> {
-    let (input, _) = multispace0(input)?;
-    Ok((input, ()))
+/// 块注释，内部的 `/* */` 需要成对出现
+fn block_comment(input: &str) -> LexResult<'_, ()> {
+    let mut depth = 0usize;
+    let mut rest = input;
+    loop {
+        if let Some(remaining) = rest.strip_prefix("/*") {
+            depth += 1;
+            rest = remaining;
+        } else if let Some(remaining) = rest.strip_prefix("*/") {
+            depth -= 1;
+            rest = remaining;
+            if depth == 0 {
+                return Ok((rest, ()));
+            }
+        } else {
+            let mut chars = rest.chars();
+            if chars.next().is_none() {
+                return Err(LexError::failure(
+                    input,
+                    2,
+                    LexErrorKind::UnterminatedComment,
+                ));
+            }
+            rest = chars.as_str();
+        }
+    }
 }
 
-fn parse_token(input: &str) -> IResult<&str, Token> {
+fn parse_token(input: &str) -> LexResult<'_, Token> {
     alt((
         parse_double_char_operators,
         parse_single_char_tokens,
         parse_number,
         parse_string,
         parse_identifier,
+        parse_illegal,
     ))(input)
 }
 
-pub fn lexer(input: &str) -> IResult<&str, Vec<Token>> {
-    let (input, _) = parse_whitespace(input)?;
-    many0(terminated(parse_token, parse_whitespace))(input)
+/// 无法识别的字符作为 ILLEGAL 词法单元，交给语法分析报告，而不是在这里停下
+fn parse_illegal(input: &str) -> LexResult<'_, Token> {
+    map(anychar, |ch| Token::new(TokenType::ILLEGAL, ch))(input)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LexErrorKind {
+    /// nom 内部的错误，表示当前位置不是这种词法单元
+    Nom,
+    InvalidEscape(String),
+    UnterminatedString,
+    UnterminatedComment,
+    InvalidNumber(String),
+}
+
+/// 词法分析的内部错误，记录出错位置开始的剩余输入以及出错部分的字节长度，
+/// 在 `lexer` 中换算成带位置的 `Error`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LexError<'a> {
+    input: &'a str,
+    len: usize,
+    kind: LexErrorKind,
+}
+
+impl<'a> LexError<'a> {
+    /// 不可恢复的错误，`alt` 不会再尝试其他分支
+    fn failure(input: &'a str, len: usize, kind: LexErrorKind) -> nom::Err<Self> {
+        nom::Err::Failure(Self { input, len, kind })
+    }
+
+    fn into_error(self, span: Span) -> anyhow::Error {
+        match self.kind {
+            LexErrorKind::InvalidEscape(escape) => Error::InvalidEscape(escape, span),
+            LexErrorKind::UnterminatedString => Error::UnterminatedString(span),
+            LexErrorKind::UnterminatedComment => Error::UnterminatedComment(span),
+            LexErrorKind::InvalidNumber(literal) => Error::InvalidNumberLiteral(literal, span),
+            LexErrorKind::Nom => Error::ReadCharError,
+        }
+        .into()
+    }
+}
+
+impl<'a> ParseError<&'a str> for LexError<'a> {
+    fn from_error_kind(input: &'a str, _kind: ErrorKind) -> Self {
+        Self {
+            input,
+            len: 0,
+            kind: LexErrorKind::Nom,
+        }
+    }
+
+    fn append(_input: &'a str, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+type LexResult<'a, T> = IResult<&'a str, T, LexError<'a>>;
+
+/// 记录已经扫描过的位置，用于把字节偏移换算成行号和列号
+struct LineTracker<'a> {
+    source: &'a str,
+    scanned: usize,
+    line: usize,
+    line_start: usize,
+}
+
+impl<'a> LineTracker<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            scanned: 0,
+            line: 1,
+            line_start: 0,
+        }
+    }
+
+    /// offset 必须单调递增
+    fn span(&mut self, start: usize, end: usize) -> Span {
+        for (idx, ch) in self.source[self.scanned..start].char_indices() {
+            if ch == '\n' {
+                self.line += 1;
+                self.line_start = self.scanned + idx + 1;
+            }
+        }
+        self.scanned = start;
+        let column = self.source[self.line_start..start].chars().count() + 1;
+        Span::new(start, end, self.line, column)
+    }
+}
+
+/// 正在扫描的插值表达式
+struct Interpolation {
+    /// 所在字符串的结束引号
+    terminator: &'static str,
+    /// 所在字符串开头引号的位置，字符串没有闭合时指向这里
+    quote: Span,
+    /// 表达式中还没有闭合的 `{` 数量，为 0 时遇到的 `}` 结束插值
+    depth: usize,
+}
+
+/// 词法分析，每个词法单元都带有它在源码中的位置，最后追加一个 EOF。
+/// 输入总是被全部消耗，无法识别的字符成为 ILLEGAL 词法单元
+pub fn lexer(input: &str) -> anyhow::Result<(&str, Vec<Token>)> {
+    let mut tracker = LineTracker::new(input);
+    let mut tokens = vec![];
+    let mut interpolations: Vec<Interpolation> = vec![];
+    let offset = |rest: &str| input.len() - rest.len();
+
+    let mut rest = input;
+    // 还没有交给词法单元的文档注释
+    let mut docs: Vec<String> = vec![];
+    loop {
+        match parse_trivia(rest) {
+            Ok((remaining, lines)) => {
+                rest = remaining;
+                docs.extend(lines);
+            }
+            Err(nom::Err::Failure(error)) => {
+                let start = offset(error.input);
+                let span = tracker.span(start, start + error.len);
+                return Err(error.into_error(span));
+            }
+            Err(_) => break,
+        }
+
+        // 插值表达式结束，继续扫描字符串剩下的部分
+        let resume = interpolations
+            .last()
+            .filter(|interpolation| interpolation.depth == 0 && rest.starts_with('}'));
+        let result = match resume {
+            Some(interpolation) => string_segment(rest, &rest[1..], interpolation.terminator).map(
+                |(remaining, (value, end))| {
+                    let token_type = match end {
+                        SegmentEnd::Terminator => TokenType::STRINGEND,
+                        SegmentEnd::Interpolation => TokenType::STRINGMIDDLE,
+                    };
+                    (remaining, Token::from_string(token_type, value))
+                },
+            ),
+            None => parse_token(rest),
+        };
+
+        match result {
+            Ok((remaining, token)) => {
+                let start = offset(rest);
+                match token.token_type() {
+                    TokenType::STRINGSTART => {
+                        let terminator = if rest.starts_with(TRIPLE_QUOTE) {
+                            TRIPLE_QUOTE
+                        } else {
+                            "\""
+                        };
+                        interpolations.push(Interpolation {
+                            terminator,
+                            quote: tracker.span(start, start + terminator.len()),
+                            depth: 0,
+                        });
+                    }
+                    TokenType::STRINGEND => {
+                        interpolations.pop();
+                    }
+                    TokenType::LBRACE => {
+                        if let Some(interpolation) = interpolations.last_mut() {
+                            interpolation.depth += 1;
+                        }
+                    }
+                    TokenType::RBRACE => {
+                        if let Some(interpolation) = interpolations.last_mut() {
+                            interpolation.depth -= 1;
+                        }
+                    }
+                    _ => {}
+                }
+                let span = tracker.span(start, offset(remaining));
+                let mut token = token.with_span(span);
+                // 文档注释只属于紧跟着的 `let` 或 `macro`，写在其他词法单元之前时当作普通注释
+                let pending = std::mem::take(&mut docs);
+                if !pending.is_empty()
+                    && matches!(token.token_type(), TokenType::LET | TokenType::MACRO)
+                {
+                    token = token.with_doc(pending.join("\n"));
+                }
+                tokens.push(token);
+                rest = remaining;
+            }
+            Err(nom::Err::Failure(error)) => {
+                let span = match (&error.kind, resume) {
+                    (LexErrorKind::UnterminatedString, Some(interpolation)) => interpolation.quote,
+                    _ => {
+                        let start = offset(error.input);
+                        tracker.span(start, start + error.len)
+                    }
+                };
+                return Err(error.into_error(span));
+            }
+            // parse_illegal 接受任意字符，只有输入结束时才会走到这里
+            Err(_) => break,
+        }
+    }
+
+    // 插值表达式没有写完输入就结束了
+    if let Some(interpolation) = interpolations.last() {
+        return Err(Error::UnterminatedString(interpolation.quote).into());
+    }
+
+    let eof = tracker.span(offset(rest), offset(rest));
+    tokens.push(Token::default().with_span(eof));
+    Ok((rest, tokens))
 }
@@ -1,4 +1,6 @@
 use super::lexer;
+use crate::error::Error;
+use crate::token::span::Span;
 use crate::token::token_type::TokenType;
 use crate::token::Token;
 
@@ -10,7 +12,7 @@ let add = fn(x, y) {
     x + y;
 };
 let result = add(five, ten);
-!-/*5;
+!-/ *5;
 5 < 10 > 5;
 
 if ( 5 < 10 ) {
@@ -118,7 +120,7 @@ if ( 5 < 10 ) {
         Token::from_string(TokenType::EOF, "\0".into()),
     ];
 
-    for (i, (tt, tok)) in tokens.into_iter().zip(tests.into_iter()).enumerate() {
+    for (i, (tt, tok)) in tokens.into_iter().zip(tests).enumerate() {
         println!("tt: {tt:?} -----  tok: {tok:?}");
         if tok.token_type() != tt.token_type() {
             println!(
@@ -133,3 +135,436 @@ if ( 5 < 10 ) {
         }
     }
 }
+
+#[test]
+fn test_lexer_macro_keyword() {
+    let input = "macro(x, y) { x + y; };";
+    let (_, tokens) = lexer(input).unwrap();
+
+    let expected = vec![
+        Token::from_string(TokenType::MACRO, "macro".into()),
+        Token::from_string(TokenType::LPAREN, "(".into()),
+        Token::from_string(TokenType::IDENT, "x".into()),
+        Token::from_string(TokenType::COMMA, ",".into()),
+        Token::from_string(TokenType::IDENT, "y".into()),
+        Token::from_string(TokenType::RPAREN, ")".into()),
+        Token::from_string(TokenType::LBRACE, "{".into()),
+        Token::from_string(TokenType::IDENT, "x".into()),
+        Token::from_string(TokenType::PLUS, "+".into()),
+        Token::from_string(TokenType::IDENT, "y".into()),
+        Token::from_string(TokenType::SEMICOLON, ";".into()),
+        Token::from_string(TokenType::RBRACE, "}".into()),
+        Token::from_string(TokenType::SEMICOLON, ";".into()),
+        Token::from_string(TokenType::EOF, "\0".into()),
+    ];
+
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn test_lexer_loop_keywords() {
+    let input = "while for in break continue";
+    let (_, tokens) = lexer(input).unwrap();
+
+    let expected = vec![
+        Token::from_string(TokenType::WHILE, "while".into()),
+        Token::from_string(TokenType::FOR, "for".into()),
+        Token::from_string(TokenType::IN, "in".into()),
+        Token::from_string(TokenType::BREAK, "break".into()),
+        Token::from_string(TokenType::CONTINUE, "continue".into()),
+        Token::from_string(TokenType::EOF, "\0".into()),
+    ];
+
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn test_lexer_float_literals() {
+    let input = "3.14 1e-9 2.5E+3 10";
+    let (_, tokens) = lexer(input).unwrap();
+
+    let expected = vec![
+        Token::from_string(TokenType::FLOAT, "3.14".into()),
+        Token::from_string(TokenType::FLOAT, "1e-9".into()),
+        Token::from_string(TokenType::FLOAT, "2.5E+3".into()),
+        Token::from_string(TokenType::INT, "10".into()),
+        Token::from_string(TokenType::EOF, "\0".into()),
+    ];
+
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn test_lexer_unicode_identifiers() {
+    let input = r#"let 名前 = "こんにちは 😀"; let café_2 = _x; 变量1"#;
+    let (_, tokens) = lexer(input).unwrap();
+
+    let expected = vec![
+        Token::from_string(TokenType::LET, "let".into()),
+        Token::from_string(TokenType::IDENT, "名前".into()),
+        Token::from_string(TokenType::ASSIGN, "=".into()),
+        Token::from_string(TokenType::STRING, "こんにちは 😀".into()),
+        Token::from_string(TokenType::SEMICOLON, ";".into()),
+        Token::from_string(TokenType::LET, "let".into()),
+        Token::from_string(TokenType::IDENT, "café_2".into()),
+        Token::from_string(TokenType::ASSIGN, "=".into()),
+        Token::from_string(TokenType::IDENT, "_x".into()),
+        Token::from_string(TokenType::SEMICOLON, ";".into()),
+        Token::from_string(TokenType::IDENT, "变量1".into()),
+        Token::from_string(TokenType::EOF, "\0".into()),
+    ];
+
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn test_lexer_string_escapes() {
+    let input = r#""a\"b\\c\n\t\r\0" "\u{1F600}\u{e9}" r"C:\dir\n" """line 1
+"quoted" \t line 2""""#;
+    let (_, tokens) = lexer(input).unwrap();
+
+    let expected = vec![
+        Token::from_string(TokenType::STRING, "a\"b\\c\n\t\r\0".into()),
+        Token::from_string(TokenType::STRING, "😀é".into()),
+        Token::from_string(TokenType::STRING, r"C:\dir\n".into()),
+        Token::from_string(TokenType::STRING, "line 1\n\"quoted\" \t line 2".into()),
+        Token::from_string(TokenType::EOF, "\0".into()),
+    ];
+
+    assert_eq!(tokens, expected);
+    // 三引号字符串跨行，之后的位置要按行计算
+    assert_eq!(tokens[4].span(), Span::new(79, 79, 2, 22));
+}
+
+#[test]
+fn test_lexer_string_errors() {
+    let tests = [
+        (
+            r#"let s = "a\qb";"#,
+            "invalid escape sequence `\\q`",
+            Span::new(10, 12, 1, 11),
+        ),
+        (
+            r#""\u{110000}""#,
+            "invalid escape sequence `\\u{110000}`",
+            Span::new(1, 11, 1, 2),
+        ),
+        (
+            r#""\u{}""#,
+            "invalid escape sequence `\\u{}`",
+            Span::new(1, 5, 1, 2),
+        ),
+        (
+            r#""\u1234""#,
+            "invalid escape sequence `\\u`",
+            Span::new(1, 3, 1, 2),
+        ),
+        (
+            "x;\n\"abc",
+            "unterminated string literal",
+            Span::new(3, 4, 2, 1),
+        ),
+        (
+            r#"""" abc ""#,
+            "unterminated string literal",
+            Span::new(0, 3, 1, 1),
+        ),
+    ];
+
+    for (input, message, span) in tests {
+        let error = lexer(input).unwrap_err();
+        assert_eq!(error.to_string(), message, "input: {input}");
+        let error = error.downcast::<Error>().unwrap();
+        assert_eq!(error.span(), Some(span), "input: {input}");
+    }
+}
+
+#[test]
+fn test_lexer_token_span() {
+    let input = "let x = 5;\n  x + \"好\";";
+    let (_, tokens) = lexer(input).unwrap();
+
+    // (字面量, start, end, line, column)
+    let expected = [
+        ("let", 0, 3, 1, 1),
+        ("x", 4, 5, 1, 5),
+        ("=", 6, 7, 1, 7),
+        ("5", 8, 9, 1, 9),
+        (";", 9, 10, 1, 10),
+        ("x", 13, 14, 2, 3),
+        ("+", 15, 16, 2, 5),
+        ("好", 17, 22, 2, 7),
+        (";", 22, 23, 2, 10),
+        ("\0", 23, 23, 2, 11),
+    ];
+
+    assert_eq!(tokens.len(), expected.len());
+    for (token, (literal, start, end, line, column)) in tokens.iter().zip(expected) {
+        assert_eq!(token.literal(), literal);
+        assert_eq!(token.span(), Span::new(start, end, line, column));
+    }
+}
+
+#[test]
+fn test_lexer_string_interpolation() {
+    use TokenType::*;
+    let tests: Vec<(&str, Vec<(TokenType, &str)>)> = vec![
+        (
+            r#""hello ${name}!""#,
+            vec![(STRINGSTART, "hello "), (IDENT, "name"), (STRINGEND, "!")],
+        ),
+        (
+            r#""${a} and ${ b + 1 }""#,
+            vec![
+                (STRINGSTART, ""),
+                (IDENT, "a"),
+                (STRINGMIDDLE, " and "),
+                (IDENT, "b"),
+                (PLUS, "+"),
+                (INT, "1"),
+                (STRINGEND, ""),
+            ],
+        ),
+        // 插值表达式里的字符串、转义以及 `}` 都不会结束外层的插值
+        (
+            r#""a ${ "x}" + "\"${1}\"" } \${b}""#,
+            vec![
+                (STRINGSTART, "a "),
+                (STRING, "x}"),
+                (PLUS, "+"),
+                (STRINGSTART, "\""),
+                (INT, "1"),
+                (STRINGEND, "\""),
+                (STRINGEND, " ${b}"),
+            ],
+        ),
+        // 哈希字面量的大括号
+        (
+            r#""${ {"k": 1}["k"] }""#,
+            vec![
+                (STRINGSTART, ""),
+                (LBRACE, "{"),
+                (STRING, "k"),
+                (COLON, ":"),
+                (INT, "1"),
+                (RBRACE, "}"),
+                (LBRACKET, "["),
+                (STRING, "k"),
+                (RBRACKET, "]"),
+                (STRINGEND, ""),
+            ],
+        ),
+        (
+            "\"\"\"say \"${x}\"\n\"\"\"",
+            vec![(STRINGSTART, "say \""), (IDENT, "x"), (STRINGEND, "\"\n")],
+        ),
+    ];
+
+    for (input, expected) in tests {
+        let (_, tokens) = lexer(input).unwrap();
+        let mut expected: Vec<Token> = expected
+            .into_iter()
+            .map(|(token_type, literal)| Token::from_string(token_type, literal.into()))
+            .collect();
+        expected.push(Token::from_string(EOF, "\0".into()));
+        assert_eq!(tokens, expected, "input: {input}");
+    }
+}
+
+#[test]
+fn test_lexer_string_interpolation_errors() {
+    let tests = [
+        (r#"x = "a ${b} c"#, Span::new(4, 5, 1, 5)),
+        (r#""a ${b"#, Span::new(0, 1, 1, 1)),
+        (r#""a ${ "b" "#, Span::new(0, 1, 1, 1)),
+        (r#""a ${ "b"#, Span::new(6, 7, 1, 7)),
+    ];
+
+    for (input, span) in tests {
+        let error = lexer(input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unterminated string literal",
+            "input: {input}"
+        );
+        let error = error.downcast::<Error>().unwrap();
+        assert_eq!(error.span(), Some(span), "input: {input}");
+    }
+}
+
+#[test]
+fn test_lexer_comments() {
+    let input = r#"// line comment
+let a = 1; // trailing
+/* block /* nested */ still comment */ let b = a / 2;
+//// not a doc comment
+let c = "// not a comment /* either */";
+"#;
+    let (rest, tokens) = lexer(input).unwrap();
+    assert_eq!(rest, "");
+
+    let literals: Vec<&str> = tokens.iter().map(|token| token.literal()).collect();
+    assert_eq!(
+        literals,
+        vec![
+            "let",
+            "a",
+            "=",
+            "1",
+            ";",
+            "let",
+            "b",
+            "=",
+            "a",
+            "/",
+            "2",
+            ";",
+            "let",
+            "c",
+            "=",
+            "// not a comment /* either */",
+            ";",
+            "\0",
+        ]
+    );
+    assert!(tokens.iter().all(|token| token.doc().is_none()));
+    // 注释之后的位置仍然正确
+    assert_eq!(tokens[5].span(), Span::new(78, 81, 3, 40));
+}
+
+#[test]
+fn test_lexer_doc_comments() {
+    let input =
+        "/// Adds two numbers.\n///\n///   Indented.\nlet add = fn(a, b) { a + b };\n/// trailing";
+    let (_, tokens) = lexer(input).unwrap();
+
+    assert_eq!(tokens[0].token_type(), &TokenType::LET);
+    assert_eq!(tokens[0].doc(), Some("Adds two numbers.\n\n  Indented."));
+    assert!(tokens[1..].iter().all(|token| token.doc().is_none()));
+
+    // 文档注释只附加到 `let` 和 `macro` 上，写在其他地方时当作普通注释，也不会留给后面的 `let`
+    let input = "/// stray
+x;
+let y = /// macro doc
+macro(a) { a };
+let z = 1;";
+    let (_, tokens) = lexer(input).unwrap();
+    let docs: Vec<_> = tokens
+        .iter()
+        .filter_map(|token| token.doc().map(|doc| (token.literal(), doc)))
+        .collect();
+    assert_eq!(docs, vec![("macro", "macro doc")]);
+}
+
+#[test]
+fn test_lexer_unterminated_comment() {
+    let error = lexer("let a = 1;\n/* outer /* inner */").unwrap_err();
+    assert_eq!(error.to_string(), "unterminated block comment");
+    let error = error.downcast::<Error>().unwrap();
+    assert_eq!(error.span(), Some(Span::new(11, 13, 2, 1)));
+}
+
+#[test]
+fn test_lexer_illegal_characters() {
+    let input = "let a = 5 @ 3; # ok";
+    let (rest, tokens) = lexer(input).unwrap();
+    assert_eq!(rest, "");
+
+    let expected = vec![
+        Token::from_string(TokenType::LET, "let".into()),
+        Token::from_string(TokenType::IDENT, "a".into()),
+        Token::from_string(TokenType::ASSIGN, "=".into()),
+        Token::from_string(TokenType::INT, "5".into()),
+        Token::from_string(TokenType::ILLEGAL, "@".into()),
+        Token::from_string(TokenType::INT, "3".into()),
+        Token::from_string(TokenType::SEMICOLON, ";".into()),
+        Token::from_string(TokenType::ILLEGAL, "#".into()),
+        Token::from_string(TokenType::IDENT, "ok".into()),
+        Token::from_string(TokenType::EOF, "\0".into()),
+    ];
+    assert_eq!(tokens, expected);
+    assert_eq!(tokens[4].span(), Span::new(10, 11, 1, 11));
+    // EOF 位于输入的末尾，说明全部输入都被消耗了
+    assert_eq!(tokens[9].span(), Span::new(19, 19, 1, 20));
+}
+
+#[test]
+fn test_lexer_number_literals() {
+    let input = "1_000_000 0xFF 0xdead_beef 0o17 0b1010_0101 1_000.000_1 1e1_0 0 007";
+    let (_, tokens) = lexer(input).unwrap();
+
+    let expected = vec![
+        Token::from_string(TokenType::INT, "1_000_000".into()),
+        Token::from_string(TokenType::INT, "0xFF".into()),
+        Token::from_string(TokenType::INT, "0xdead_beef".into()),
+        Token::from_string(TokenType::INT, "0o17".into()),
+        Token::from_string(TokenType::INT, "0b1010_0101".into()),
+        Token::from_string(TokenType::FLOAT, "1_000.000_1".into()),
+        Token::from_string(TokenType::FLOAT, "1e1_0".into()),
+        Token::from_string(TokenType::INT, "0".into()),
+        Token::from_string(TokenType::INT, "007".into()),
+        Token::from_string(TokenType::EOF, "\0".into()),
+    ];
+
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn test_lexer_invalid_number_literals() {
+    let tests = [
+        ("let x = 1abc;", "1abc", Span::new(8, 12, 1, 9)),
+        ("7else", "7else", Span::new(0, 5, 1, 1)),
+        ("1_", "1_", Span::new(0, 2, 1, 1)),
+        ("1__0", "1__0", Span::new(0, 4, 1, 1)),
+        ("0b102", "0b102", Span::new(0, 5, 1, 1)),
+        ("0x", "0x", Span::new(0, 2, 1, 1)),
+        ("0xG1", "0xG1", Span::new(0, 4, 1, 1)),
+        ("1e", "1e", Span::new(0, 2, 1, 1)),
+        ("2.5_", "2.5_", Span::new(0, 4, 1, 1)),
+    ];
+
+    for (input, literal, span) in tests {
+        let error = lexer(input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("invalid number literal `{literal}`"),
+            "input: {input}"
+        );
+        let error = error.downcast::<Error>().unwrap();
+        assert_eq!(error.span(), Some(span), "input: {input}");
+    }
+}
+
+#[test]
+fn test_lexer_operators() {
+    use TokenType::*;
+    let input = "<= >= < > % && || & | ^ << >> ~ ** * == != !";
+    let (_, tokens) = lexer(input).unwrap();
+
+    let expected: Vec<Token> = [
+        (LTEQ, "<="),
+        (GTEQ, ">="),
+        (LT, "<"),
+        (GT, ">"),
+        (PERCENT, "%"),
+        (AND, "&&"),
+        (OR, "||"),
+        (AMPERSAND, "&"),
+        (PIPE, "|"),
+        (CARET, "^"),
+        (SHL, "<<"),
+        (SHR, ">>"),
+        (TILDE, "~"),
+        (POWER, "**"),
+        (ASTERISK, "*"),
+        (EQ, "=="),
+        (NOTEQ, "!="),
+        (BANG, "!"),
+        (EOF, "\0"),
+    ]
+    .into_iter()
+    .map(|(token_type, literal)| Token::from_string(token_type, literal.into()))
+    .collect();
+
+    assert_eq!(tokens, expected);
+}
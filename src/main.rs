@@ -1,17 +1,88 @@
+use clap::{Parser, Subcommand};
 use star_kirby_lang::repl;
+use star_kirby_lang::runner;
 use star_kirby_lang::telemetry;
 use std::io;
+use std::io::IsTerminal;
+
+/// The Monkey programming language.
+///
+/// Without a subcommand, runs FILE if given, reads a program from standard input
+/// when it is piped, and otherwise starts the REPL.
+#[derive(Parser, Debug)]
+#[command(version, args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Script to run, same as `run FILE`
+    file: Option<String>,
+
+    /// Arguments passed to the program as the `args` array
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    args: Vec<String>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run a script file
+    Run {
+        file: String,
+
+        /// Arguments passed to the program as the `args` array
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Start the interactive REPL
+    Repl {
+        /// Arguments passed to the program as the `args` array
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Evaluate a program given on the command line and print its value
+    Eval {
+        /// Source code to evaluate
+        #[arg(short = 'e', long = "expr", value_name = "CODE")]
+        code: String,
+
+        /// Arguments passed to the program as the `args` array
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+}
 
 fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
     let subscriber =
         telemetry::get_subscriber("star-kirby-lang".into(), "info".into(), std::io::stdout);
     telemetry::init_subscriber(subscriber)?;
+
+    let code = run(cli)?;
+    std::process::exit(code)
+}
+
+/// 按命令行运行解释器，返回进程的退出码
+fn run(cli: Cli) -> anyhow::Result<i32> {
+    let code = match (cli.command, cli.file) {
+        (Some(Command::Run { file, args }), _) => runner::run_file(&file, &args),
+        (None, Some(file)) => runner::run_file(&file, &cli.args),
+        (Some(Command::Eval { code, args }), _) => runner::run_source("<eval>", &code, &args, true),
+        (Some(Command::Repl { args }), _) => start_repl(&args)?,
+        (None, None) if io::stdin().is_terminal() => start_repl(&cli.args)?,
+        (None, None) => runner::run_stdin(&cli.args),
+    };
+    Ok(code)
+}
+
+fn start_repl(args: &[String]) -> anyhow::Result<i32> {
     println!(
         "Hello {}! This is the Monkey programming language!",
         whoami::username()
     );
     println!("Feel free to type in commands");
-    repl::start(io::stdin(), io::stdout())?;
-
-    Ok(())
+    match repl::start_stdio(args) {
+        Ok(()) => Ok(runner::EXIT_SUCCESS),
+        Err(error) => runner::exit_code(&error).ok_or(error),
+    }
 }
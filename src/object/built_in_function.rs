@@ -1,17 +1,26 @@
 use crate::ast::NodeInterface;
 use crate::error::Error;
 use crate::object::array::Array;
+use crate::object::float::Float;
 use crate::object::integer::Integer;
 use crate::object::Null;
 use crate::object::ObjectType;
 use crate::object::{Object, ObjectInterface};
+use crate::token::span::Span;
+use std::cell::RefCell;
 use std::fmt::{Display, Formatter};
+use std::io::Write;
 
 const BUILD_FUNC: &str = "builtin function";
 
 type BuildBoxFuncType = Box<fn(Vec<Object>) -> anyhow::Result<Object>>;
 type BuildFuncType = fn(Vec<Object>) -> anyhow::Result<Object>;
 
+thread_local! {
+    /// 不为 None 时 `puts` 的输出写到这里，见 `set_output`
+    static OUTPUT: RefCell<Option<Box<dyn Write>>> = const { RefCell::new(None) };
+}
+
 #[derive(Debug, Clone, PartialOrd, PartialEq, Eq, Ord, Hash)]
 pub struct Builtin {
     built_in_function: BuildBoxFuncType,
@@ -40,12 +49,13 @@ pub fn process_len(args: Vec<Object>) -> anyhow::Result<Object> {
         return Err(Error::WrongNumberOfArguments {
             got: args.len(),
             want: 1,
+            span: Span::default(),
         }
         .into());
     }
 
     match args[0].clone() {
-        Object::String(value) => Ok(Integer::new(value.value().len() as isize).into()),
+        Object::String(value) => Ok(Integer::new(value.len() as isize).into()),
         Object::Array(value) => Ok(Integer::new(value.len() as isize).into()),
         _ => Err(Error::ArgumentNotSupported {
             got: args[0].object_type().to_string(),
@@ -59,6 +69,7 @@ pub fn array_first_element(args: Vec<Object>) -> anyhow::Result<Object> {
         return Err(Error::WrongNumberOfArguments {
             got: args.len(),
             want: 1,
+            span: Span::default(),
         }
         .into());
     }
@@ -81,6 +92,7 @@ pub fn array_last_element(args: Vec<Object>) -> anyhow::Result<Object> {
         return Err(Error::WrongNumberOfArguments {
             got: args.len(),
             want: 1,
+            span: Span::default(),
         }
         .into());
     }
@@ -106,6 +118,7 @@ pub fn array_rest_element(args: Vec<Object>) -> anyhow::Result<Object> {
         return Err(Error::WrongNumberOfArguments {
             got: args.len(),
             want: 1,
+            span: Span::default(),
         }
         .into());
     }
@@ -132,6 +145,7 @@ pub fn array_push_element(args: Vec<Object>) -> anyhow::Result<Object> {
         return Err(Error::WrongNumberOfArguments {
             got: args.len(),
             want: 2,
+            span: Span::default(),
         }
         .into());
     }
@@ -153,16 +167,187 @@ pub fn array_push_element(args: Vec<Object>) -> anyhow::Result<Object> {
     }
 }
 
+/// 每个参数输出一行。默认写到进程的标准输出，用 `set_output` 设置过输出时写到设置的输出中。
+/// 每次调用都会立即写出，写入失败时返回错误
 pub fn puts(args: Vec<Object>) -> anyhow::Result<Object> {
-    for arg in args {
-        println!("{arg}");
-    }
+    OUTPUT.with_borrow_mut(|output| -> anyhow::Result<()> {
+        match output {
+            Some(output) => {
+                for arg in args {
+                    writeln!(output, "{arg}")?;
+                }
+                output.flush()?;
+            }
+            None => args.iter().for_each(|arg| println!("{arg}")),
+        }
+        Ok(())
+    })?;
     Ok(Null.into())
 }
 
+/// 把当前线程中 `puts` 的输出换成 `output`，返回之前设置的输出。
+/// 传入 None 时恢复为进程的标准输出
+pub fn set_output(output: Option<Box<dyn Write>>) -> Option<Box<dyn Write>> {
+    OUTPUT.replace(output)
+}
+
+fn check_arguments(args: &[Object], want: usize) -> anyhow::Result<()> {
+    if args.len() != want {
+        return Err(Error::WrongNumberOfArguments {
+            got: args.len(),
+            want,
+            span: Span::default(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+fn argument_not_supported(name: &str, arg: &Object) -> anyhow::Error {
+    Error::ArgumentTypeNotSupported {
+        name: name.into(),
+        got: arg.object_type().to_string(),
+        span: Span::default(),
+    }
+    .into()
+}
+
+/// 把切片的边界限制在 `[0, len]` 之内
+fn slice_bound(value: &Object, len: usize) -> anyhow::Result<usize> {
+    match value {
+        Object::Integer(value) if *value < Integer::new(0) => Ok(0),
+        Object::Integer(value) => Ok(value
+            .to_isize()
+            .map_or(len, |value| (value as usize).min(len))),
+        arg => Err(argument_not_supported("slice", arg)),
+    }
+}
+
+/// slice(x, start, end)：截取数组或字符串的 `[start, end)` 部分，省略 end 时截到末尾。
+/// 字符串按字符计算下标，越界的下标会被限制在有效范围内
+pub fn slice(args: Vec<Object>) -> anyhow::Result<Object> {
+    if args.len() != 2 && args.len() != 3 {
+        return Err(Error::WrongNumberOfArguments {
+            got: args.len(),
+            want: 3,
+            span: Span::default(),
+        }
+        .into());
+    }
+
+    let bounds = |len| -> anyhow::Result<(usize, usize)> {
+        let start = slice_bound(&args[1], len)?;
+        let end = match args.get(2) {
+            Some(end) => slice_bound(end, len)?.max(start),
+            None => len,
+        };
+        Ok((start, end))
+    };
+
+    match &args[0] {
+        Object::Array(value) => {
+            let (start, end) = bounds(value.len())?;
+            Ok(Array::new(value.elements()[start..end].to_vec()).into())
+        }
+        Object::String(value) => {
+            let (start, end) = bounds(value.len())?;
+            Ok(value.slice(start, end).into())
+        }
+        arg => Err(argument_not_supported("slice", arg)),
+    }
+}
+
+/// 浮点数向零取整成整数，NaN 和无穷大无法转换
+fn float_to_integer(value: f64) -> anyhow::Result<Object> {
+    match Integer::from_f64(value) {
+        Some(value) => Ok(value.into()),
+        None => Err(Error::CannotConvert {
+            value: Float::new(value).to_string(),
+            target: ObjectType::Integer.to_string(),
+            span: Span::default(),
+        }
+        .into()),
+    }
+}
+
+/// int(x)：浮点数向零取整，字符串按十进制整数解析
+pub fn to_int(args: Vec<Object>) -> anyhow::Result<Object> {
+    check_arguments(&args, 1)?;
+
+    match &args[0] {
+        Object::Integer(value) => Ok(value.clone().into()),
+        Object::Float(value) => float_to_integer(value.value()),
+        Object::String(value) => match Integer::parse(value.value().trim()) {
+            Some(value) => Ok(value.into()),
+            None => Err(Error::CannotConvert {
+                value: value.value().into(),
+                target: ObjectType::Integer.to_string(),
+                span: Span::default(),
+            }
+            .into()),
+        },
+        arg => Err(argument_not_supported("int", arg)),
+    }
+}
+
+/// float(x)：整数转换成最接近的浮点数，字符串按浮点数字面量解析
+pub fn to_float(args: Vec<Object>) -> anyhow::Result<Object> {
+    check_arguments(&args, 1)?;
+
+    match &args[0] {
+        Object::Integer(value) => Ok(value.to_f64().into()),
+        Object::Float(value) => Ok((*value).into()),
+        Object::String(value) => match value.value().trim().parse::<f64>() {
+            Ok(value) => Ok(value.into()),
+            Err(_) => Err(Error::CannotConvert {
+                value: value.value().into(),
+                target: ObjectType::Float.to_string(),
+                span: Span::default(),
+            }
+            .into()),
+        },
+        arg => Err(argument_not_supported("float", arg)),
+    }
+}
+
+/// floor/ceil/round 的公共部分：整数原样返回，浮点数取整后转换成整数
+fn round_with(name: &str, args: Vec<Object>, round: fn(f64) -> f64) -> anyhow::Result<Object> {
+    check_arguments(&args, 1)?;
+
+    match &args[0] {
+        Object::Integer(value) => Ok(value.clone().into()),
+        Object::Float(value) => float_to_integer(round(value.value())),
+        arg => Err(argument_not_supported(name, arg)),
+    }
+}
+
+pub fn floor(args: Vec<Object>) -> anyhow::Result<Object> {
+    round_with("floor", args, f64::floor)
+}
+
+pub fn ceil(args: Vec<Object>) -> anyhow::Result<Object> {
+    round_with("ceil", args, f64::ceil)
+}
+
+/// 四舍五入，恰好在中间时远离零
+pub fn round(args: Vec<Object>) -> anyhow::Result<Object> {
+    round_with("round", args, f64::round)
+}
+
+/// 总是返回浮点数，负数的平方根是 NaN
+pub fn sqrt(args: Vec<Object>) -> anyhow::Result<Object> {
+    check_arguments(&args, 1)?;
+
+    match &args[0] {
+        Object::Integer(value) => Ok(value.to_f64().sqrt().into()),
+        Object::Float(value) => Ok(value.value().sqrt().into()),
+        arg => Err(argument_not_supported("sqrt", arg)),
+    }
+}
+
 impl ObjectInterface for Builtin {
     fn object_type(&self) -> ObjectType {
-        ObjectType::Array
+        ObjectType::Builtin
     }
 
     fn inspect(&self) -> String {
@@ -186,3 +371,30 @@ impl TryFrom<Object> for Builtin {
         }
     }
 }
+
+/// exit([code])：结束程序，退出码默认为 0，只能是 0 到 255 之间的整数。
+/// 以 `Error::Exit` 的形式一路向外传播，由运行程序的一方决定怎样退出
+pub fn exit(args: Vec<Object>) -> anyhow::Result<Object> {
+    let code = match args.as_slice() {
+        [] => 0,
+        [Object::Integer(value)] => value
+            .to_isize()
+            .and_then(|value| i32::try_from(value).ok())
+            .filter(|code| (0..=255).contains(code))
+            .ok_or_else(|| Error::CannotConvert {
+                value: value.to_string(),
+                target: "exit code".into(),
+                span: Span::default(),
+            })?,
+        [arg] => return Err(argument_not_supported("exit", arg)),
+        args => {
+            return Err(Error::WrongNumberOfArguments {
+                got: args.len(),
+                want: 1,
+                span: Span::default(),
+            }
+            .into())
+        }
+    };
+    Err(Error::Exit(code).into())
+}
@@ -1,26 +1,97 @@
 use crate::object::Object;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::rc::{Rc, Weak};
 use std::{collections::BTreeMap, fmt::Display};
 
-#[derive(Debug, Clone, PartialOrd, PartialEq, Eq, Ord, Hash)]
-pub struct Environment {
+/// 一层作用域：当前层的绑定以及外层作用域
+#[derive(Default)]
+struct Scope {
     store: BTreeMap<String, Object>,
-    outer: Option<Box<Environment>>,
+    outer: Option<Environment>,
+}
+
+/// 对作用域的引用。保存在自己捕获的作用域里的函数使用弱引用，见 `Environment::store`
+#[derive(Clone)]
+enum ScopeRef {
+    Strong(Rc<RefCell<Scope>>),
+    Weak(Weak<RefCell<Scope>>),
 }
 
-impl Default for Environment {
+impl Default for ScopeRef {
     fn default() -> Self {
-        Self::new()
+        Self::Strong(Rc::default())
+    }
+}
+
+impl ScopeRef {
+    fn as_ptr(&self) -> *const RefCell<Scope> {
+        match self {
+            Self::Strong(scope) => Rc::as_ptr(scope),
+            Self::Weak(scope) => scope.as_ptr(),
+        }
+    }
+}
+
+/// 环境是对作用域的共享引用，clone 之后和原来的环境指向同一个作用域。
+/// 闭包因此能看到定义之后才加入的绑定（包括函数自己，从而支持递归）。
+///
+/// 函数可以通过别的绑定间接地引用自己捕获的环境，所以比较、排序和哈希
+/// 都按作用域的身份（指针）而不是内容进行，否则会无限递归。
+#[derive(Clone, Default)]
+pub struct Environment {
+    scope: ScopeRef,
+}
+
+impl PartialEq for Environment {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.scope.as_ptr(), other.scope.as_ptr())
+    }
+}
+
+impl Eq for Environment {}
+
+impl PartialOrd for Environment {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Environment {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.scope.as_ptr().cmp(&other.scope.as_ptr())
+    }
+}
+
+impl Hash for Environment {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.scope.as_ptr().hash(state);
+    }
+}
+
+/// 只输出绑定的名字，避免沿着循环引用无限展开
+impl std::fmt::Debug for Environment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let scope = self.scope();
+        let scope = scope.borrow();
+        f.debug_struct("Environment")
+            .field("names", &scope.store.keys().collect::<Vec<_>>())
+            .field("outer", &scope.outer)
+            .finish()
     }
 }
 
 impl Display for Environment {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for (key, value) in self.store.iter() {
+        let scope = self.scope();
+        let scope = scope.borrow();
+        for (key, value) in scope.store.iter() {
             writeln!(f, "{}: {}", key, value)?;
         }
         writeln!(f,)?;
-        if self.outer.is_some() {
-            writeln!(f, "{}", self.outer.as_ref().unwrap())
+        if let Some(outer) = &scope.outer {
+            writeln!(f, "{outer}")
         } else {
             writeln!(f,)
         }
@@ -29,29 +100,123 @@ impl Display for Environment {
 
 impl Environment {
     pub fn new() -> Self {
-        Self {
-            store: BTreeMap::new(),
-            outer: None,
-        }
+        Self::default()
     }
 
     pub fn new_enclosed_environment(outer: Environment) -> Self {
-        let mut env = Environment::new();
-        env.outer = Some(Box::new(outer));
+        let env = Environment::new();
+        env.scope().borrow_mut().outer = Some(outer);
         env
     }
 
-    pub fn get(&self, name: String) -> Option<&Object> {
-        let ret = self.store.get(&name);
-        if ret.is_none() && self.outer.is_some() {
-            return self.outer.as_ref().unwrap().get(name);
+    /// 弱引用只存在于保存在作用域自己里面的函数中，能访问到这个函数时作用域一定还在
+    fn scope(&self) -> Rc<RefCell<Scope>> {
+        match &self.scope {
+            ScopeRef::Strong(scope) => scope.clone(),
+            ScopeRef::Weak(scope) => scope.upgrade().expect("never failed"),
         }
+    }
+
+    /// 指向同一个作用域的强引用
+    pub(crate) fn upgrade(&self) -> Self {
+        Self {
+            scope: ScopeRef::Strong(self.scope()),
+        }
+    }
 
-        ret
+    /// 指向同一个作用域的弱引用
+    pub(crate) fn downgrade(&self) -> Self {
+        Self {
+            scope: ScopeRef::Weak(Rc::downgrade(&self.scope())),
+        }
     }
 
-    pub fn store(&mut self, name: String, value: Object) -> Object {
-        self.store.insert(name, value.clone());
+    /// 作用域是否还没有被释放
+    #[cfg(test)]
+    pub(crate) fn is_alive(&self) -> bool {
+        match &self.scope {
+            ScopeRef::Strong(_) => true,
+            ScopeRef::Weak(scope) => scope.strong_count() > 0,
+        }
+    }
+
+    pub fn get(&self, name: String) -> Option<Object> {
+        let scope = self.scope();
+        let scope = scope.borrow();
+        match (scope.store.get(&name), &scope.outer) {
+            (None, Some(outer)) => outer.get(name),
+            (ret, _) => ret.cloned().map(upgrade_captured),
+        }
+    }
+
+    /// 修改已有的绑定：沿着外层作用域找到定义它的那一层并更新，
+    /// 没有任何一层定义过时返回 None
+    pub fn assign(&self, name: String, value: Object) -> Option<Object> {
+        let scope = self.scope();
+        let mut scope = scope.borrow_mut();
+        if let Some(slot) = scope.store.get_mut(&name) {
+            *slot = self.downgrade_captured(value.clone());
+            return Some(value);
+        }
+        scope.outer.as_ref()?.assign(name, value)
+    }
+
+    /// 当前环境以及所有外层环境中绑定的名字，按字典序排列并去重
+    pub fn names(&self) -> Vec<String> {
+        let scope = self.scope();
+        let scope = scope.borrow();
+        let mut names: Vec<String> = scope.store.keys().cloned().collect();
+        if let Some(outer) = &scope.outer {
+            names.extend(outer.names());
+        }
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// 清空当前层的所有绑定
+    pub fn clear(&self) {
+        self.scope().borrow_mut().store.clear();
+    }
+
+    pub fn store(&self, name: String, value: Object) -> Object {
+        let stored = self.downgrade_captured(value.clone());
+        self.scope().borrow_mut().store.insert(name, stored);
         value
     }
+
+    /// `let f = fn() { ... }` 把函数保存在它自己捕获的作用域里，
+    /// 如果保存的是强引用，作用域和函数互相引用，永远不会被释放。
+    /// 这种情况下函数只保存弱引用，取出来的时候再换回强引用。
+    ///
+    /// 内层函数返回的闭包再保存到外层作用域时（`let c = makeCounter();`），
+    /// 闭包捕获的作用域经过 `outer` 引用外层作用域，这样的循环仍然无法释放
+    fn downgrade_captured(&self, value: Object) -> Object {
+        match value {
+            Object::Function(value) if value.env() == self => {
+                let env = value.env().downgrade();
+                value.with_env(env).into()
+            }
+            Object::Macro(value) if value.env() == self => {
+                let env = value.env().downgrade();
+                value.with_env(env).into()
+            }
+            value => value,
+        }
+    }
+}
+
+/// 从作用域中取出的函数换回强引用，这样它离开作用域之后（比如作为返回值）仍然可用
+fn upgrade_captured(value: Object) -> Object {
+    match value {
+        Object::Function(value) => {
+            let env = value.env().upgrade();
+            value.with_env(env).into()
+        }
+        Object::Macro(value) => {
+            let env = value.env().upgrade();
+            value.with_env(env).into()
+        }
+        value => value,
+    }
 }
@@ -0,0 +1,117 @@
+use crate::ast::NodeInterface;
+use crate::error::Error;
+use crate::object::integer::Integer;
+use crate::object::{Object, ObjectInterface, ObjectType};
+use crate::token::span::Span;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::hash::Hasher;
+
+/// 浮点数，运算遵循 IEEE 754。
+///
+/// `Object` 需要 Eq/Ord/Hash 才能作为哈希的键，所以这里的比较不是 IEEE 比较：
+/// `-0.0` 和 `0.0` 视为同一个值，所有 NaN 视为同一个值，其余按 `f64::total_cmp` 排序。
+/// 求值时的 `==`、`<` 等运算符在 evaluator 中单独按 IEEE 语义处理。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Float {
+    value: f64,
+}
+
+impl Float {
+    pub fn new(value: f64) -> Self {
+        Self { value }
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// 比较和哈希使用的规范值
+    fn canonical(&self) -> f64 {
+        if self.value == 0.0 {
+            0.0
+        } else if self.value.is_nan() {
+            f64::NAN
+        } else {
+            self.value
+        }
+    }
+
+    /// 作为哈希键时的规则：NaN 不能作为键；
+    /// 没有小数部分的浮点数和对应的整数是同一个键（`h[1.0]` 等价于 `h[1]`）
+    pub fn hash_key(&self) -> anyhow::Result<Object> {
+        if self.value.is_nan() {
+            return Err(Error::UnusableAsHashKey(self.to_string(), Span::default()).into());
+        }
+        match Integer::from_f64(self.value) {
+            Some(value) if self.value.fract() == 0.0 => Ok(value.into()),
+            _ => Ok(Float::new(self.canonical()).into()),
+        }
+    }
+}
+
+impl PartialEq for Float {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Float {}
+
+impl PartialOrd for Float {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Float {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.canonical().total_cmp(&other.canonical())
+    }
+}
+
+impl std::hash::Hash for Float {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.canonical().to_bits().hash(state);
+    }
+}
+
+impl ObjectInterface for Float {
+    fn object_type(&self) -> ObjectType {
+        ObjectType::Float
+    }
+
+    fn inspect(&self) -> String {
+        format!("{self}")
+    }
+}
+
+/// 总是带上小数点或指数，和整数区分开：`3.0`、`1e-9`、`inf`、`NaN`
+impl Display for Float {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.value)
+    }
+}
+
+impl NodeInterface for Float {
+    fn token_literal(&self) -> &str {
+        "float"
+    }
+}
+
+impl TryFrom<Object> for Float {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Object) -> Result<Self, Self::Error> {
+        match value {
+            Object::Float(value) => Ok(value),
+            _ => Err(Error::UnknownObjectType.into()),
+        }
+    }
+}
+
+impl From<f64> for Float {
+    fn from(value: f64) -> Self {
+        Self::new(value)
+    }
+}
@@ -5,6 +5,8 @@ use crate::object::environment::Environment;
 use crate::object::{Object, ObjectInterface, ObjectType};
 use std::fmt::{Display, Formatter};
 
+/// 函数对象。捕获的环境按引用共享，所以两个函数只有在参数、函数体相同，
+/// 并且捕获的是同一个环境时才相等
 #[derive(Debug, Clone, PartialOrd, PartialEq, Eq, Ord, Hash)]
 pub struct Function {
     parameters: Vec<Identifier>,
@@ -31,6 +33,11 @@ impl Function {
     pub fn env(&self) -> &Environment {
         &self.env
     }
+
+    /// 换成指向同一个作用域的另一个引用，见 `Environment::store`
+    pub(crate) fn with_env(self, env: Environment) -> Self {
+        Self { env, ..self }
+    }
 }
 
 impl Display for Function {
@@ -20,6 +20,10 @@ impl Hash {
         &self.pairs
     }
 
+    pub fn pairs_mut(&mut self) -> &mut BTreeMap<Object, Object> {
+        &mut self.pairs
+    }
+
     pub fn len(&self) -> usize {
         self.pairs.len()
     }
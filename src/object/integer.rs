@@ -1,20 +1,308 @@
 use crate::ast::NodeInterface;
 use crate::error::Error;
 use crate::object::{Object, ObjectInterface, ObjectType};
+#[cfg(feature = "bigint")]
+use num_bigint::BigInt;
+#[cfg(feature = "bigint")]
+use num_traits::{FromPrimitive, ToPrimitive};
+use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
 
-#[derive(Debug, Clone, PartialOrd, PartialEq, Eq, Ord, Hash)]
+/// 溢出后用来重新计算的类型，没有开启 bigint 时不会被用到
+#[cfg(feature = "bigint")]
+type Wide = BigInt;
+#[cfg(not(feature = "bigint"))]
+type Wide = isize;
+
+/// 开启 bigint 时 `**` 和 `<<` 的结果最多允许的位数，超过时按溢出报错，
+/// 避免 `2 ** 4000000000` 这样的运算耗尽时间和内存
+pub const MAX_BIGINT_BITS: u64 = 1 << 22;
+
+/// 绝对值的二进制位数，0 的位数是 0
+#[cfg(feature = "bigint")]
+fn bit_len(value: &Wide) -> u64 {
+    value.bits()
+}
+
+#[cfg(not(feature = "bigint"))]
+fn bit_len(value: &Wide) -> u64 {
+    u64::from(isize::BITS - value.unsigned_abs().leading_zeros())
+}
+
+/// 整数的内部表示：能放进 isize 时总是 Small，
+/// 开启 bigint 特性后，超出 isize 范围的值提升为 Big。
+/// 两种表示不会重叠，所以派生的相等和哈希与数值相等一致。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Repr {
+    Small(isize),
+    #[cfg(feature = "bigint")]
+    Big(BigInt),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Integer {
-    value: isize,
+    repr: Repr,
 }
 
 impl Integer {
     pub fn new(value: isize) -> Self {
-        Self { value }
+        Self {
+            repr: Repr::Small(value),
+        }
+    }
+
+    /// 解析十进制整数，超出可表示范围时返回 None
+    pub fn parse(literal: &str) -> Option<Self> {
+        Self::parse_radix(literal, 10)
+    }
+
+    /// 解析源码中的整数字面量：可以带 `0x`、`0o`、`0b` 前缀，数字之间可以有 `_`
+    pub fn parse_literal(literal: &str) -> Option<Self> {
+        let digits = literal.replace('_', "");
+        match digits.get(..2) {
+            Some("0x") => Self::parse_radix(&digits[2..], 16),
+            Some("0o") => Self::parse_radix(&digits[2..], 8),
+            Some("0b") => Self::parse_radix(&digits[2..], 2),
+            _ => Self::parse_radix(&digits, 10),
+        }
+    }
+
+    fn parse_radix(digits: &str, radix: u32) -> Option<Self> {
+        if let Ok(value) = isize::from_str_radix(digits, radix) {
+            return Some(Self::new(value));
+        }
+        #[cfg(feature = "bigint")]
+        if let Some(value) = BigInt::parse_bytes(digits.as_bytes(), radix) {
+            return Some(Self::from_big(value));
+        }
+        None
+    }
+
+    /// 能放进 isize 时返回对应的值
+    pub fn to_isize(&self) -> Option<isize> {
+        match &self.repr {
+            Repr::Small(value) => Some(*value),
+            #[cfg(feature = "bigint")]
+            Repr::Big(_) => None,
+        }
+    }
+
+    /// 转换成浮点数，超出精度的部分舍入
+    pub fn to_f64(&self) -> f64 {
+        match &self.repr {
+            Repr::Small(value) => *value as f64,
+            #[cfg(feature = "bigint")]
+            Repr::Big(value) => value.to_f64().unwrap_or(f64::NAN),
+        }
+    }
+
+    /// 向零取整转换浮点数，NaN、无穷大以及超出可表示范围时返回 None
+    #[cfg(feature = "bigint")]
+    pub fn from_f64(value: f64) -> Option<Self> {
+        BigInt::from_f64(value.trunc()).map(Self::from_big)
+    }
+
+    /// 向零取整转换浮点数，NaN、无穷大以及超出可表示范围时返回 None
+    #[cfg(not(feature = "bigint"))]
+    pub fn from_f64(value: f64) -> Option<Self> {
+        let value = value.trunc();
+        // isize::MAX as f64 会舍入成 2^63，本身已经超出范围
+        (value >= isize::MIN as f64 && value < isize::MAX as f64).then(|| Self::new(value as isize))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.to_isize() == Some(0)
+    }
+
+    pub fn is_negative(&self) -> bool {
+        *self < Self::new(0)
+    }
+
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        self.binary(other, isize::checked_add, |l, r| Some(l + r))
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        self.binary(other, isize::checked_sub, |l, r| Some(l - r))
+    }
+
+    pub fn checked_mul(&self, other: &Self) -> Option<Self> {
+        self.binary(other, isize::checked_mul, |l, r| Some(l * r))
+    }
+
+    /// 向零取整的除法，除数为零时返回 None
+    pub fn checked_div(&self, other: &Self) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+        self.binary(other, isize::checked_div, |l, r| Some(l / r))
+    }
+
+    /// 取余数，符号和被除数相同（与 `/` 的向零取整一致），除数为零时返回 None
+    pub fn checked_rem(&self, other: &Self) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+        self.binary(other, isize::checked_rem, |l, r| Some(l % r))
     }
 
-    pub fn value(&self) -> isize {
-        self.value
+    pub fn checked_neg(&self) -> Option<Self> {
+        Self::new(0).checked_sub(self)
+    }
+
+    pub fn checked_pow(&self, exponent: u32) -> Option<Self> {
+        self.unary(
+            |value| value.checked_pow(exponent),
+            // |value| >= 2^(bits - 1)，所以结果至少有 (bits - 1) * exponent 位
+            |value| {
+                let bits = (bit_len(&value).saturating_sub(1)).saturating_mul(exponent.into());
+                (bits <= MAX_BIGINT_BITS).then(|| value.pow(exponent))
+            },
+        )
+    }
+
+    /// 按位与、或、异或以及取反都按二进制补码计算，不会溢出
+    pub fn bitand(&self, other: &Self) -> Self {
+        self.binary(other, |l, r| Some(l & r), |l, r| Some(l & r))
+            .unwrap_or_default()
+    }
+
+    pub fn bitor(&self, other: &Self) -> Self {
+        self.binary(other, |l, r| Some(l | r), |l, r| Some(l | r))
+            .unwrap_or_default()
+    }
+
+    pub fn bitxor(&self, other: &Self) -> Self {
+        self.binary(other, |l, r| Some(l ^ r), |l, r| Some(l ^ r))
+            .unwrap_or_default()
+    }
+
+    pub fn not(&self) -> Self {
+        self.unary(|value| Some(!value), |value| Some(!value))
+            .unwrap_or_default()
+    }
+
+    /// 左移，结果超出可表示范围时返回 None
+    pub fn checked_shl(&self, amount: u32) -> Option<Self> {
+        self.unary(
+            |value| {
+                let shifted = value.checked_shl(amount)?;
+                (shifted >> amount == value).then_some(shifted)
+            },
+            |value| {
+                let bits = bit_len(&value);
+                (bits == 0 || bits + u64::from(amount) <= MAX_BIGINT_BITS).then(|| value << amount)
+            },
+        )
+    }
+
+    /// 算术右移，向负无穷取整
+    pub fn shr(&self, amount: u32) -> Self {
+        self.unary(
+            |value| Some(value >> amount.min(isize::BITS - 1)),
+            |value| Some(value >> amount),
+        )
+        .unwrap_or_default()
+    }
+
+    /// 先按 isize 计算，溢出后（开启 bigint 时）改用大整数重新计算
+    #[cfg(feature = "bigint")]
+    fn binary(
+        &self,
+        other: &Self,
+        small: fn(isize, isize) -> Option<isize>,
+        big: fn(Wide, Wide) -> Option<Wide>,
+    ) -> Option<Self> {
+        if let (Repr::Small(left), Repr::Small(right)) = (&self.repr, &other.repr) {
+            if let Some(value) = small(*left, *right) {
+                return Some(Self::new(value));
+            }
+        }
+        big(self.to_big(), other.to_big()).map(Self::from_big)
+    }
+
+    #[cfg(not(feature = "bigint"))]
+    fn binary(
+        &self,
+        other: &Self,
+        small: fn(isize, isize) -> Option<isize>,
+        _big: fn(Wide, Wide) -> Option<Wide>,
+    ) -> Option<Self> {
+        let (Repr::Small(left), Repr::Small(right)) = (&self.repr, &other.repr);
+        small(*left, *right).map(Self::new)
+    }
+
+    /// 和 `binary` 一样，先按 isize 计算，失败后（开启 bigint 时）改用大整数
+    #[cfg(feature = "bigint")]
+    fn unary(
+        &self,
+        small: impl Fn(isize) -> Option<isize>,
+        big: impl Fn(Wide) -> Option<Wide>,
+    ) -> Option<Self> {
+        if let Repr::Small(value) = &self.repr {
+            if let Some(value) = small(*value) {
+                return Some(Self::new(value));
+            }
+        }
+        big(self.to_big()).map(Self::from_big)
+    }
+
+    #[cfg(not(feature = "bigint"))]
+    fn unary(
+        &self,
+        small: impl Fn(isize) -> Option<isize>,
+        _big: impl Fn(Wide) -> Option<Wide>,
+    ) -> Option<Self> {
+        let Repr::Small(value) = &self.repr;
+        small(*value).map(Self::new)
+    }
+
+    #[cfg(feature = "bigint")]
+    fn to_big(&self) -> BigInt {
+        match &self.repr {
+            Repr::Small(value) => BigInt::from(*value),
+            Repr::Big(value) => value.clone(),
+        }
+    }
+
+    /// 大整数能放回 isize 时换回 Small，保证表示唯一
+    #[cfg(feature = "bigint")]
+    fn from_big(value: BigInt) -> Self {
+        match value.to_isize() {
+            Some(value) => Self::new(value),
+            None => Self {
+                repr: Repr::Big(value),
+            },
+        }
+    }
+}
+
+impl Default for Integer {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl PartialOrd for Integer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// 按数值比较，两种表示之间也能比较大小
+impl Ord for Integer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (&self.repr, &other.repr) {
+            (Repr::Small(left), Repr::Small(right)) => left.cmp(right),
+            #[cfg(feature = "bigint")]
+            _ => self.to_big().cmp(&other.to_big()),
+        }
+    }
+}
+
+impl PartialEq<isize> for Integer {
+    fn eq(&self, other: &isize) -> bool {
+        self.to_isize() == Some(*other)
     }
 }
 
@@ -30,7 +318,11 @@ impl ObjectInterface for Integer {
 
 impl Display for Integer {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.value)
+        match &self.repr {
+            Repr::Small(value) => write!(f, "{value}"),
+            #[cfg(feature = "bigint")]
+            Repr::Big(value) => write!(f, "{value}"),
+        }
     }
 }
 
@@ -53,6 +345,6 @@ impl TryFrom<Object> for Integer {
 
 impl From<isize> for Integer {
     fn from(value: isize) -> Self {
-        Self { value }
+        Self::new(value)
     }
 }
@@ -0,0 +1,48 @@
+use crate::ast::NodeInterface;
+use crate::error::Error;
+use crate::object::{Object, ObjectInterface, ObjectType};
+use std::fmt::{Display, Formatter};
+
+/// `break` 和 `continue` 产生的控制流信号，
+/// 和 ReturnValue 一样沿着块语句向外传递，直到被最近的循环处理
+#[derive(Debug, Clone, Copy, PartialOrd, PartialEq, Eq, Ord, Hash)]
+pub enum LoopControl {
+    Break,
+    Continue,
+}
+
+impl Display for LoopControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.token_literal())
+    }
+}
+
+impl NodeInterface for LoopControl {
+    fn token_literal(&self) -> &str {
+        match self {
+            Self::Break => "break",
+            Self::Continue => "continue",
+        }
+    }
+}
+
+impl ObjectInterface for LoopControl {
+    fn object_type(&self) -> ObjectType {
+        ObjectType::LoopControl
+    }
+
+    fn inspect(&self) -> String {
+        format!("{self}")
+    }
+}
+
+impl TryFrom<Object> for LoopControl {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Object) -> Result<Self, Self::Error> {
+        match value {
+            Object::LoopControl(value) => Ok(value),
+            _ => Err(Error::UnknownObjectType.into()),
+        }
+    }
+}
@@ -1 +1,86 @@
+use crate::ast::statement::block::BlockStatement;
+use crate::ast::{Identifier, NodeInterface};
+use crate::error::Error;
+use crate::object::environment::Environment;
+use crate::object::{Object, ObjectInterface, ObjectType};
+use std::fmt::{Display, Formatter};
+
 pub mod quote;
+
+const MACRO: &str = "macro";
+
+#[derive(Debug, Clone, PartialOrd, PartialEq, Eq, Ord, Hash)]
+pub struct Macro {
+    parameters: Vec<Identifier>,
+    body: BlockStatement,
+    env: Environment,
+}
+
+impl Macro {
+    pub fn new(parameters: Vec<Identifier>, body: BlockStatement, env: Environment) -> Self {
+        Self {
+            parameters,
+            body,
+            env,
+        }
+    }
+
+    pub fn parameters(&self) -> &Vec<Identifier> {
+        &self.parameters
+    }
+
+    pub fn body(&self) -> &BlockStatement {
+        &self.body
+    }
+
+    pub fn env(&self) -> &Environment {
+        &self.env
+    }
+
+    /// 换成指向同一个作用域的另一个引用，见 `Environment::store`
+    pub(crate) fn with_env(self, env: Environment) -> Self {
+        Self { env, ..self }
+    }
+}
+
+impl Display for Macro {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "macro(")?;
+        for (i, p) in self.parameters.iter().enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{p}")?;
+        }
+        writeln!(f, ") {{")?;
+        writeln!(f, "{}", self.body)?;
+        write!(f, "}}")
+    }
+}
+
+impl ObjectInterface for Macro {
+    fn object_type(&self) -> ObjectType {
+        ObjectType::Macro
+    }
+
+    fn inspect(&self) -> String {
+        format!("{self}")
+    }
+}
+
+impl NodeInterface for Macro {
+    fn token_literal(&self) -> &str {
+        MACRO
+    }
+}
+
+impl TryFrom<Object> for Macro {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Object) -> Result<Self, Self::Error> {
+        match value {
+            Object::Macro(value) => Ok(value),
+            _ => Err(Error::UnknownObjectType.into()),
+        }
+    }
+}
@@ -2,11 +2,14 @@ use crate::ast::NodeInterface;
 use crate::object::array::Array;
 use crate::object::boolean::Boolean;
 use crate::object::built_in_function::Builtin;
+use crate::object::float::Float;
 use crate::object::function::Function;
 use crate::object::hash::Hash;
 use crate::object::integer::Integer;
+use crate::object::loop_control::LoopControl;
 use crate::object::null::Null;
 use crate::object::r#macro::quote::Quote;
+use crate::object::r#macro::Macro;
 use crate::object::return_value::ReturnValue;
 use crate::object::string::StringObj;
 use std::fmt::{Debug, Display, Formatter};
@@ -15,9 +18,11 @@ pub mod array;
 pub mod boolean;
 pub mod built_in_function;
 pub mod environment;
+pub mod float;
 pub mod function;
 pub mod hash;
 pub mod integer;
+pub mod loop_control;
 pub mod r#macro;
 pub mod null;
 pub mod return_value;
@@ -26,6 +31,7 @@ pub mod string;
 #[derive(Debug, PartialEq, Eq)]
 pub enum ObjectType {
     Integer,
+    Float,
     Boolean,
     Null,
     Return,
@@ -35,12 +41,15 @@ pub enum ObjectType {
     Array,
     Hash,
     Queue,
+    Macro,
+    LoopControl,
 }
 
 impl Display for ObjectType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Integer => write!(f, "INTEGER"),
+            Self::Float => write!(f, "FLOAT"),
             Self::Boolean => write!(f, "BOOLEAN"),
             Self::Null => write!(f, "NULL"),
             Self::Return => write!(f, "RETURN"),
@@ -50,6 +59,8 @@ impl Display for ObjectType {
             Self::Array => write!(f, "ARRAY"),
             Self::Hash => write!(f, "HASH"),
             Self::Queue => write!(f, "QUOTE"),
+            Self::Macro => write!(f, "MACRO"),
+            Self::LoopControl => write!(f, "LOOP_CONTROL"),
         }
     }
 }
@@ -58,6 +69,7 @@ impl Display for ObjectType {
 pub enum Object {
     Boolean(Boolean),
     Integer(Integer),
+    Float(Float),
     ReturnValue(ReturnValue),
     Function(Function),
     String(StringObj),
@@ -66,6 +78,8 @@ pub enum Object {
     Null(Null),
     Hash(Hash),
     Quote(Quote),
+    Macro(Macro),
+    LoopControl(LoopControl),
 }
 
 impl From<Boolean> for Object {
@@ -86,6 +100,18 @@ impl From<Integer> for Object {
     }
 }
 
+impl From<Float> for Object {
+    fn from(value: Float) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<f64> for Object {
+    fn from(value: f64) -> Self {
+        Self::Float(Float::new(value))
+    }
+}
+
 impl From<ReturnValue> for Object {
     fn from(value: ReturnValue) -> Self {
         Self::ReturnValue(value)
@@ -134,11 +160,24 @@ impl From<Quote> for Object {
     }
 }
 
+impl From<LoopControl> for Object {
+    fn from(value: LoopControl) -> Self {
+        Self::LoopControl(value)
+    }
+}
+
+impl From<Macro> for Object {
+    fn from(value: Macro) -> Self {
+        Self::Macro(value)
+    }
+}
+
 impl Display for Object {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Boolean(value) => write!(f, "{value}"),
             Self::Integer(value) => write!(f, "{value}"),
+            Self::Float(value) => write!(f, "{value}"),
             Self::ReturnValue(value) => write!(f, "{value}"),
             Self::Function(value) => write!(f, "{value}"),
             Self::String(value) => write!(f, "{value}"),
@@ -147,6 +186,8 @@ impl Display for Object {
             Self::Null(value) => write!(f, "{value}"),
             Self::Hash(value) => write!(f, "{value}"),
             Self::Quote(value) => write!(f, "{value}"),
+            Self::Macro(value) => write!(f, "{value}"),
+            Self::LoopControl(value) => write!(f, "{value}"),
         }
     }
 }
@@ -156,6 +197,7 @@ impl NodeInterface for Object {
         match self {
             Self::Boolean(value) => value.token_literal(),
             Self::Integer(value) => value.token_literal(),
+            Self::Float(value) => value.token_literal(),
             Self::ReturnValue(value) => value.token_literal(),
             Self::Function(value) => value.token_literal(),
             Self::String(value) => value.token_literal(),
@@ -164,6 +206,8 @@ impl NodeInterface for Object {
             Self::Null(value) => value.token_literal(),
             Self::Hash(value) => value.token_literal(),
             Self::Quote(value) => value.token_literal(),
+            Self::Macro(value) => value.token_literal(),
+            Self::LoopControl(value) => value.token_literal(),
         }
     }
 }
@@ -173,6 +217,7 @@ impl ObjectInterface for Object {
         match self {
             Self::Boolean(value) => value.object_type(),
             Self::Integer(value) => value.object_type(),
+            Self::Float(value) => value.object_type(),
             Self::ReturnValue(value) => value.object_type(),
             Self::Function(value) => value.object_type(),
             Self::String(value) => value.object_type(),
@@ -181,6 +226,8 @@ impl ObjectInterface for Object {
             Self::Null(value) => value.object_type(),
             Self::Hash(value) => value.object_type(),
             Self::Quote(value) => value.object_type(),
+            Self::Macro(value) => value.object_type(),
+            Self::LoopControl(value) => value.object_type(),
         }
     }
 
@@ -188,6 +235,7 @@ impl ObjectInterface for Object {
         match self {
             Self::Boolean(value) => value.inspect(),
             Self::Integer(value) => value.inspect(),
+            Self::Float(value) => value.inspect(),
             Self::ReturnValue(value) => value.inspect(),
             Self::Function(value) => value.inspect(),
             Self::String(value) => value.inspect(),
@@ -196,6 +244,8 @@ impl ObjectInterface for Object {
             Self::Null(value) => value.inspect(),
             Self::Hash(value) => value.inspect(),
             Self::Quote(value) => value.inspect(),
+            Self::Macro(value) => value.inspect(),
+            Self::LoopControl(value) => value.inspect(),
         }
     }
 }
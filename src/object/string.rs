@@ -16,6 +16,28 @@ impl StringObj {
     pub fn value(&self) -> &String {
         &self.value
     }
+
+    /// 长度按 Unicode 标量值（char）计算，而不是字节数
+    pub fn len(&self) -> usize {
+        self.value.chars().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    /// 第 `index` 个字符，越界时返回 None
+    pub fn char_at(&self, index: usize) -> Option<StringObj> {
+        self.value
+            .chars()
+            .nth(index)
+            .map(|ch| StringObj::new(ch.to_string()))
+    }
+
+    /// 按字符截取 `[start, end)`，调用方保证 start <= end <= len
+    pub fn slice(&self, start: usize, end: usize) -> StringObj {
+        StringObj::new(self.value.chars().skip(start).take(end - start).collect())
+    }
 }
 
 impl Display for StringObj {
@@ -4,24 +4,35 @@ pub mod parser_tracing;
 mod tests;
 
 use crate::ast::expression::array::ArrayLiteral;
+use crate::ast::expression::assign::Assign;
 use crate::ast::expression::boolean::Boolean;
 use crate::ast::expression::call::Call;
+use crate::ast::expression::float::FloatLiteral;
 use crate::ast::expression::function::FunctionLiteral;
 use crate::ast::expression::hash::HashLiteral;
 use crate::ast::expression::if_expression::If;
 use crate::ast::expression::index::Index;
 use crate::ast::expression::infix::Infix;
 use crate::ast::expression::integer::IntegerLiteral;
+use crate::ast::expression::interpolated_string::InterpolatedString;
+use crate::ast::expression::macro_literal::MacroLiteral;
 use crate::ast::expression::prefix::Prefix;
 use crate::ast::expression::string::StringLiteral;
 use crate::ast::expression::Expression;
 use crate::ast::statement::block::BlockStatement;
+use crate::ast::statement::break_statement::BreakStatement;
+use crate::ast::statement::continue_statement::ContinueStatement;
 use crate::ast::statement::expression::ExpressionStatement;
+use crate::ast::statement::for_statement::ForStatement;
 use crate::ast::statement::let_statement::LetStatement;
 use crate::ast::statement::return_statement::ReturnStatement;
+use crate::ast::statement::while_statement::WhileStatement;
 use crate::ast::statement::Statement;
 use crate::ast::{Identifier, Program};
 use crate::error::Error;
+use crate::evaluator::{STACK_GROW_SIZE, STACK_RED_ZONE};
+use crate::object::float::Float;
+use crate::object::integer::Integer;
 use crate::parser::operator_priority::OperatorPriority;
 use crate::parser::operator_priority::OperatorPriority::{LOWEST, PREFIX};
 use crate::token::token_type::TokenType;
@@ -54,12 +65,22 @@ pub struct Parser<'a> {
     current_position: usize, // 添加一个字段来追踪当前位置
     prefix_parse_fns: HashMap<TokenType, PrefixParseFn<'a>>,
     infix_parse_fns: HashMap<TokenType, InferParseFn<'a>>,
+    /// 解析过程中收集到的所有错误，出错后会同步到下一个 `;` 或 `}` 继续解析
+    errors: Vec<Error>,
+    /// 当前正在解析的表达式的嵌套层数，见 `MAX_NESTING_DEPTH`
+    depth: usize,
 }
 
+/// 表达式的最大嵌套层数。解析、求值和输出语法树都是递归的，
+/// 超过时报错，而不是让过深的输入耗尽栈空间
+pub const MAX_NESTING_DEPTH: usize = 256;
+
 impl<'a> Parser<'a> {
     pub fn new(lexer: Vec<Token>) -> anyhow::Result<Self> {
         let mut lexer = lexer;
-        lexer.push(Token::new(TokenType::EOF, '\0')); // 在末尾添加EOF标记，这里假设 '\0' 表示EOF
+        if lexer.last().map(Token::token_type) != Some(&TokenType::EOF) {
+            lexer.push(Token::new(TokenType::EOF, '\0')); // 在末尾添加EOF标记，这里假设 '\0' 表示EOF
+        }
 
         let mut parser = Parser {
             lexer,
@@ -68,12 +89,16 @@ impl<'a> Parser<'a> {
             current_position: 0,
             prefix_parse_fns: HashMap::default(),
             infix_parse_fns: HashMap::default(),
+            errors: vec![],
+            depth: 0,
         };
 
         parser.register_prefix(TokenType::IDENT, Self::parse_identifier);
         parser.register_prefix(TokenType::INT, Self::parser_integer_literal);
+        parser.register_prefix(TokenType::FLOAT, Self::parse_float_literal);
         parser.register_prefix(TokenType::BANG, Self::parse_prefix_expression);
         parser.register_prefix(TokenType::MINUS, Self::parse_prefix_expression);
+        parser.register_prefix(TokenType::TILDE, Self::parse_prefix_expression);
 
         parser.register_prefix(TokenType::TRUE, Self::parse_boolean);
         parser.register_prefix(TokenType::FALSE, Self::parse_boolean);
@@ -81,8 +106,10 @@ impl<'a> Parser<'a> {
         parser.register_prefix(TokenType::IF, Self::parse_if_expression);
         parser.register_prefix(TokenType::FUNCTION, Self::parse_function_literal);
         parser.register_prefix(TokenType::STRING, Self::parse_string);
+        parser.register_prefix(TokenType::STRINGSTART, Self::parse_interpolated_string);
         parser.register_prefix(TokenType::LBRACKET, Self::parse_array_literal);
         parser.register_prefix(TokenType::LBRACE, Self::parse_hash_literal);
+        parser.register_prefix(TokenType::MACRO, Self::parse_macro_literal);
 
         parser.register_infix(TokenType::PLUS, Self::parse_infix_expression);
         parser.register_infix(TokenType::MINUS, Self::parse_infix_expression);
@@ -92,8 +119,20 @@ impl<'a> Parser<'a> {
         parser.register_infix(TokenType::NOTEQ, Self::parse_infix_expression);
         parser.register_infix(TokenType::LT, Self::parse_infix_expression);
         parser.register_infix(TokenType::GT, Self::parse_infix_expression);
+        parser.register_infix(TokenType::LTEQ, Self::parse_infix_expression);
+        parser.register_infix(TokenType::GTEQ, Self::parse_infix_expression);
+        parser.register_infix(TokenType::PERCENT, Self::parse_infix_expression);
+        parser.register_infix(TokenType::POWER, Self::parse_infix_expression);
+        parser.register_infix(TokenType::AND, Self::parse_infix_expression);
+        parser.register_infix(TokenType::OR, Self::parse_infix_expression);
+        parser.register_infix(TokenType::AMPERSAND, Self::parse_infix_expression);
+        parser.register_infix(TokenType::PIPE, Self::parse_infix_expression);
+        parser.register_infix(TokenType::CARET, Self::parse_infix_expression);
+        parser.register_infix(TokenType::SHL, Self::parse_infix_expression);
+        parser.register_infix(TokenType::SHR, Self::parse_infix_expression);
         parser.register_infix(TokenType::LPAREN, Self::parser_call_expression);
         parser.register_infix(TokenType::LBRACKET, Self::parse_index_expression);
+        parser.register_infix(TokenType::ASSIGN, Self::parse_assign_expression);
 
         // 读取两个词法单元，以设置 curToken 和 peekToken
         parser.next_token()?;
@@ -102,16 +141,6 @@ impl<'a> Parser<'a> {
         Ok(parser)
     }
 
-    // TODO 因为使用 PrefixParseFn 和InferParseFn 的原因，其中的第一个参数是parser
-    #[tracing::instrument(name = "parse_program", skip(self, parse), level = "debug")]
-    fn update_parser(&mut self, parse: Parser<'a>) {
-        self.lexer = parse.lexer;
-        self.current_token = parse.current_token;
-        self.peek_token = parse.peek_token;
-        self.prefix_parse_fns = parse.prefix_parse_fns;
-        self.infix_parse_fns = parse.infix_parse_fns;
-    }
-
     #[tracing::instrument(name = "parse_program", skip(self), level = "debug")]
     fn next_token(&mut self) -> anyhow::Result<()> {
         self.current_token = self.peek_token.clone();
@@ -119,11 +148,19 @@ impl<'a> Parser<'a> {
             self.peek_token = self.lexer[self.current_position].clone();
             self.current_position += 1;
         } else {
-            self.peek_token = Token::new(TokenType::EOF, '\0'); // 返回一个EOF标记，而不是错误
+            // 返回末尾的EOF标记（带有源码结束位置），而不是错误
+            self.peek_token = self.lexer.last().cloned().unwrap_or_default();
         }
         Ok(())
     }
 
+    /// 解析过程中收集到的错误
+    pub fn errors(&self) -> &[Error] {
+        &self.errors
+    }
+
+    /// 解析整个程序。遇到错误不会立即返回，而是记录下来并跳到下一条语句，
+    /// 最后如果有错误，以 `Error::ParserErrors` 的形式一次性返回全部错误
     #[tracing::instrument(name = "parse_identifier", skip(self), level = "debug")]
     pub fn parse_program(&mut self) -> anyhow::Result<Program> {
         tracing::trace!("current_token = {:?}", self.current_token);
@@ -131,12 +168,42 @@ impl<'a> Parser<'a> {
 
         // Now fix this to EOF
         while !self.cur_token_is(TokenType::EOF) {
-            let stmt = self.parse_statement()?;
-            program.statements.push(stmt);
+            match self.parse_statement() {
+                Ok(stmt) => program.statements.push(stmt),
+                Err(error) => {
+                    self.record_error(error)?;
+                    self.synchronize()?;
+                }
+            }
             self.next_token()?;
         }
 
-        Ok(program)
+        if self.errors.is_empty() {
+            Ok(program)
+        } else {
+            Err(Error::ParserErrors(self.errors.clone()).into())
+        }
+    }
+
+    /// 记录解析错误，非解析错误（不是 `Error` 类型）直接返回
+    fn record_error(&mut self, error: anyhow::Error) -> anyhow::Result<()> {
+        match error.downcast::<Error>() {
+            Ok(Error::ParserErrors(errors)) => self.errors.extend(errors),
+            Ok(error) => self.errors.push(error),
+            Err(error) => return Err(error),
+        }
+        Ok(())
+    }
+
+    /// 出错后跳过剩余的词法单元，直到当前词法单元是 `;`、`}` 或 EOF
+    fn synchronize(&mut self) -> anyhow::Result<()> {
+        while !self.cur_token_is(TokenType::SEMICOLON)
+            && !self.cur_token_is(TokenType::RBRACE)
+            && !self.cur_token_is(TokenType::EOF)
+        {
+            self.next_token()?;
+        }
+        Ok(())
     }
 
     #[tracing::instrument(name = "parse_statement", skip(self), level = "debug")]
@@ -145,6 +212,10 @@ impl<'a> Parser<'a> {
         match self.current_token.token_type() {
             TokenType::LET => Ok(self.parse_let_statement()?.into()),
             TokenType::RETURN => Ok(self.parse_return_statement()?.into()),
+            TokenType::WHILE => Ok(self.parse_while_statement()?.into()),
+            TokenType::FOR => Ok(self.parse_for_statement()?.into()),
+            TokenType::BREAK => Ok(self.parse_break_statement()?.into()),
+            TokenType::CONTINUE => Ok(self.parse_continue_statement()?.into()),
             _ => {
                 // default parse expression statement
                 Ok(self.parse_expression_statement()?.into())
@@ -165,28 +236,16 @@ impl<'a> Parser<'a> {
         tracing::trace!("current_token = {:?}", self.current_token);
         let mut stmt = LetStatement::new(self.current_token.clone());
         tracing::trace!("stmt = {stmt})");
-        if self.expect_peek(TokenType::IDENT).is_err() {
-            return Err(Error::CannotFindTokenType {
-                ty: TokenType::IDENT.to_string(),
-            }
-            .into());
-        }
+        self.expect_peek(TokenType::IDENT)?;
         stmt.update_identifier(Identifier::new(
             self.current_token.clone(),
             self.current_token.literal().into(),
         ));
         tracing::trace!("stmt = {stmt}");
-        if self.expect_peek(TokenType::ASSIGN).is_err() {
-            return Err(Error::CannotFindTokenType {
-                ty: TokenType::ASSIGN.to_string(),
-            }
-            .into());
-        }
+        self.expect_peek(TokenType::ASSIGN)?;
         self.next_token()?;
         stmt.update_expression(self.parse_expression(LOWEST)?);
-        while !self.cur_token_is(TokenType::SEMICOLON) {
-            self.next_token()?;
-        }
+        self.expect_statement_end()?;
         tracing::trace!("stmt = {stmt}");
         Ok(stmt)
     }
@@ -199,9 +258,61 @@ impl<'a> Parser<'a> {
         self.next_token()?;
         // add equal expression
         stmt.update_expression(self.parse_expression(LOWEST)?);
-        while !self.cur_token_is(TokenType::SEMICOLON) {
-            self.next_token()?;
-        }
+        self.expect_statement_end()?;
+        Ok(stmt)
+    }
+
+    /// 解析 while 语句
+    #[tracing::instrument(name = "parse_while_statement", skip(self), level = "debug")]
+    fn parse_while_statement(&mut self) -> anyhow::Result<WhileStatement> {
+        let mut stmt = WhileStatement::new(self.current_token.clone());
+
+        self.expect_peek(TokenType::LPAREN)?;
+        self.next_token()?;
+        stmt.update_condition(self.parse_expression(LOWEST)?);
+        self.expect_peek(TokenType::RPAREN)?;
+
+        self.expect_peek(TokenType::LBRACE)?;
+        stmt.update_body(self.parse_block_statement()?);
+        self.expect_block_statement_end()?;
+
+        Ok(stmt)
+    }
+
+    /// 解析 for 语句
+    #[tracing::instrument(name = "parse_for_statement", skip(self), level = "debug")]
+    fn parse_for_statement(&mut self) -> anyhow::Result<ForStatement> {
+        let mut stmt = ForStatement::new(self.current_token.clone());
+
+        self.expect_peek(TokenType::LPAREN)?;
+        self.expect_peek(TokenType::IDENT)?;
+        stmt.update_variable(Identifier::new(
+            self.current_token.clone(),
+            self.current_token.literal().into(),
+        ));
+        self.expect_peek(TokenType::IN)?;
+        self.next_token()?;
+        stmt.update_iterable(self.parse_expression(LOWEST)?);
+        self.expect_peek(TokenType::RPAREN)?;
+
+        self.expect_peek(TokenType::LBRACE)?;
+        stmt.update_body(self.parse_block_statement()?);
+        self.expect_block_statement_end()?;
+
+        Ok(stmt)
+    }
+
+    /// 解析 break 语句
+    fn parse_break_statement(&mut self) -> anyhow::Result<BreakStatement> {
+        let stmt = BreakStatement::new(self.current_token.clone());
+        self.expect_statement_end()?;
+        Ok(stmt)
+    }
+
+    /// 解析 continue 语句
+    fn parse_continue_statement(&mut self) -> anyhow::Result<ContinueStatement> {
+        let stmt = ContinueStatement::new(self.current_token.clone());
+        self.expect_statement_end()?;
         Ok(stmt)
     }
 
@@ -213,9 +324,13 @@ impl<'a> Parser<'a> {
         tracing::trace!("current_token = {:?}", self.current_token);
         let mut stmt = ExpressionStatement::new(self.current_token.clone());
         tracing::trace!("before ExpressionStatement = {stmt}");
-        stmt.update_expression(self.parse_expression(LOWEST)?);
-        if self.peek_token_is(TokenType::SEMICOLON) {
-            self.next_token()?;
+        let expression = self.parse_expression(LOWEST)?;
+        let is_if = matches!(expression, Expression::If(_));
+        stmt.update_expression(expression);
+        if is_if {
+            self.expect_block_statement_end()?;
+        } else {
+            self.expect_statement_end()?;
         }
         tracing::trace!("after ExpressionStatement = {stmt}");
         Ok(stmt)
@@ -224,59 +339,73 @@ impl<'a> Parser<'a> {
     /// parse expression
     #[tracing::instrument(name = "parse_expression", skip(self, precedence), level = "debug")]
     fn parse_expression(&mut self, precedence: OperatorPriority) -> anyhow::Result<Expression> {
+        let depth = self.depth;
+        // 嵌套的每一层要经过好几个解析函数，栈快用完时换到新的栈段
+        let expression = stacker::maybe_grow(STACK_RED_ZONE, STACK_GROW_SIZE, || {
+            self.parse_nested_expression(precedence)
+        });
+        self.depth = depth;
+        expression
+    }
+
+    /// 解析函数都是函数指针，复制出来之后直接在当前的解析器上调用
+    fn parse_nested_expression(
+        &mut self,
+        precedence: OperatorPriority,
+    ) -> anyhow::Result<Expression> {
         tracing::trace!("current_token = {:?}", self.current_token);
-        // TODO clone evn to temp value
-        // TODO 因为使用 PrefixParseFn 和InferParseFn 的原因，其中的第一个参数是parser
-        let mut parser = self.clone();
-
-        let prefix = self.prefix_parse_fns.get(self.current_token.token_type());
-
-        // create temp infix parse fns for immutable checks
-        let temp_infix_parse_fns = self.infix_parse_fns.clone();
-
-        if prefix.is_none() {
+        self.enter_nesting()?;
+        if self.cur_token_is(TokenType::ILLEGAL) {
+            return Err(illegal_character(&self.current_token).into());
+        }
+        let Some(prefix) = self
+            .prefix_parse_fns
+            .get(self.current_token.token_type())
+            .copied()
+        else {
             return Err(Error::NoPrefixParseFunctionFound(
                 self.current_token.token_type().to_string(),
+                self.current_token.span(),
             )
             .into());
-        }
-        // FIXME: THIS IS OK
-        let prefix = prefix.unwrap();
+        };
 
-        let mut left_exp = prefix(&mut parser)?;
-        // TODO 因为使用 PrefixParseFn 和InferParseFn 的原因，其中的第一个参数是parser
-        self.update_parser(parser);
-        // TODO 因为使用 PrefixParseFn 和InferParseFn 的原因，其中的第一个参数是parser
+        let mut left_exp = prefix(self)?;
         tracing::trace!("left expression = {left_exp:?}");
 
         while !self.peek_token_is(TokenType::SEMICOLON) && precedence < self.peek_precedence() {
             tracing::trace!("peek_token = {:?}", self.peek_token);
-            let infix = temp_infix_parse_fns.get(self.peek_token.token_type());
-            if infix.is_none() {
+            let Some(infix) = self
+                .infix_parse_fns
+                .get(self.peek_token.token_type())
+                .copied()
+            else {
                 return Ok(left_exp);
-            }
+            };
 
             self.next_token()?;
-            // TODO
-            // 第二次分析道这里的bug
-            // then update parser, because there update self useed by next_token
-            //  因为使用 PrefixParseFn 和InferParseFn 的原因，其中的第一个参数是parser
-            parser = self.clone();
-
-            let infix = infix.unwrap();
-            left_exp = infix(&mut parser, left_exp)?;
-
-            // TODO又是这个错误
-            // TODO 因为使用 PrefixParseFn 和InferParseFn 的原因，其中的第一个参数是parser
-            // update env with temp value
-            self.update_parser(parser);
+            // 每个中缀运算符都让左边的表达式多嵌套一层，`1 + 1 + ...` 这样的长链同样受限制
+            self.enter_nesting()?;
+            left_exp = infix(self, left_exp)?;
         }
 
         tracing::trace!("end current_token = {:?}", self.current_token);
-        // 总结只要有变更Self的地方，都需要更新self
         Ok(left_exp)
     }
 
+    /// 进入新的一层嵌套，超过 `MAX_NESTING_DEPTH` 时报错
+    fn enter_nesting(&mut self) -> anyhow::Result<()> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return Err(Error::NestingTooDeep {
+                limit: MAX_NESTING_DEPTH,
+                span: self.current_token.span(),
+            }
+            .into());
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
     /// parse string
     #[tracing::instrument(name = "parse_string", skip(self), level = "debug")]
     fn parse_string(&mut self) -> anyhow::Result<Expression> {
@@ -287,6 +416,26 @@ impl<'a> Parser<'a> {
         .into())
     }
 
+    /// parse interpolated string，`"a ${x} b ${y} c"` 的词法单元依次是
+    /// STRINGSTART x STRINGMIDDLE y STRINGEND
+    #[tracing::instrument(name = "parse_interpolated_string", skip(self), level = "debug")]
+    fn parse_interpolated_string(&mut self) -> anyhow::Result<Expression> {
+        let mut string = InterpolatedString::new(self.current_token.clone());
+
+        loop {
+            self.next_token()?;
+            let expression = self.parse_expression(LOWEST)?;
+            if self.peek_token_is(TokenType::STRINGMIDDLE) {
+                self.next_token()?;
+                string.push(expression, &self.current_token);
+            } else {
+                self.expect_peek(TokenType::STRINGEND)?;
+                string.push(expression, &self.current_token);
+                return Ok(string.into());
+            }
+        }
+    }
+
     /// parse identifier
     #[tracing::instrument(name = "parse_identifier", skip(self), level = "debug")]
     fn parse_identifier(&mut self) -> anyhow::Result<Expression> {
@@ -312,12 +461,30 @@ impl<'a> Parser<'a> {
         // un_trace(trace("parseIntegerLiteral".into()));
 
         let mut literal = IntegerLiteral::new(self.current_token.clone());
-        let value = self.current_token.literal().parse::<isize>()?;
+        let value = Integer::parse_literal(self.current_token.literal()).ok_or_else(|| {
+            Error::IntegerLiteralOutOfRange(
+                self.current_token.literal().into(),
+                self.current_token.span(),
+            )
+        })?;
 
         literal.update_value(value);
         Ok(literal.into())
     }
 
+    /// parse float literal，超出范围的字面量按 IEEE 规则变成无穷大
+    fn parse_float_literal(&mut self) -> anyhow::Result<Expression> {
+        let mut literal = FloatLiteral::new(self.current_token.clone());
+        let value = self
+            .current_token
+            .literal()
+            .replace('_', "")
+            .parse::<f64>()?;
+
+        literal.update_value(Float::new(value));
+        Ok(literal.into())
+    }
+
     /// parse prefix expression
     #[tracing::instrument(name = "parse_prefix_expression", skip(self), level = "debug")]
     fn parse_prefix_expression(&mut self) -> anyhow::Result<Expression> {
@@ -341,7 +508,11 @@ impl<'a> Parser<'a> {
 
         tracing::trace!("before InfixExpression = {expression}");
 
-        let precedence = self.cur_precedence();
+        // `**` 是右结合的：右侧按低一级的优先级解析，这样后面的 `**` 会先结合
+        let precedence = match self.current_token.token_type() {
+            TokenType::POWER => PREFIX,
+            _ => self.cur_precedence(),
+        };
 
         self.next_token()?;
 
@@ -352,6 +523,21 @@ impl<'a> Parser<'a> {
         Ok(expression.into())
     }
 
+    /// parse assign expression
+    /// 右侧按最低优先级解析，所以 `a = b = 1` 是右结合的
+    #[tracing::instrument(name = "parse_assign_expression", skip(self), level = "debug")]
+    fn parse_assign_expression(&mut self, target: Expression) -> anyhow::Result<Expression> {
+        if !Assign::is_assignable(&target) {
+            return Err(Error::InvalidAssignmentTarget(target.to_string(), target.span()).into());
+        }
+
+        let mut expression = Assign::new(self.current_token.clone(), target);
+        self.next_token()?;
+        expression.update_value(self.parse_expression(LOWEST)?);
+
+        Ok(expression.into())
+    }
+
     /// parse ground expression
     #[tracing::instrument(name = "parse_grouped_expression", skip(self), level = "debug")]
     fn parse_grouped_expression(&mut self) -> anyhow::Result<Expression> {
@@ -359,12 +545,7 @@ impl<'a> Parser<'a> {
 
         let exp = self.parse_expression(LOWEST)?;
 
-        if self.expect_peek(TokenType::RPAREN).is_err() {
-            return Err(Error::CannotFindTokenType {
-                ty: TokenType::RPAREN.to_string(),
-            }
-            .into());
-        }
+        self.expect_peek(TokenType::RPAREN)?;
 
         Ok(exp)
     }
@@ -374,42 +555,22 @@ impl<'a> Parser<'a> {
     fn parse_if_expression(&mut self) -> anyhow::Result<Expression> {
         let mut expression = If::new(self.current_token.clone());
 
-        if self.expect_peek(TokenType::LPAREN).is_err() {
-            return Err(Error::CannotFindTokenType {
-                ty: TokenType::LPAREN.to_string(),
-            }
-            .into());
-        }
+        self.expect_peek(TokenType::LPAREN)?;
 
         self.next_token()?;
 
         expression.update_expression(self.parse_expression(LOWEST)?);
 
-        if self.expect_peek(TokenType::RPAREN).is_err() {
-            return Err(Error::CannotFindTokenType {
-                ty: TokenType::RPAREN.to_string(),
-            }
-            .into());
-        }
+        self.expect_peek(TokenType::RPAREN)?;
 
-        if self.expect_peek(TokenType::LBRACE).is_err() {
-            return Err(Error::CannotFindTokenType {
-                ty: TokenType::LBRACE.to_string(),
-            }
-            .into());
-        }
+        self.expect_peek(TokenType::LBRACE)?;
 
         expression.update_consequence(self.parse_block_statement()?);
 
         if self.peek_token_is(TokenType::ELSE) {
             self.next_token()?;
 
-            if self.expect_peek(TokenType::LBRACE).is_err() {
-                return Err(Error::CannotFindTokenType {
-                    ty: TokenType::LBRACE.to_string(),
-                }
-                .into());
-            }
+            self.expect_peek(TokenType::LBRACE)?;
 
             expression.update_alternative(self.parse_block_statement()?);
         }
@@ -424,13 +585,25 @@ impl<'a> Parser<'a> {
 
         self.next_token()?;
 
-        // TODO this should be EOF, but this is ILLEGAL
-        while !self.cur_token_is(TokenType::RBRACE) && !self.cur_token_is(TokenType::ILLEGAL) {
-            let stmt = self.parse_statement()?;
-            block.push_statement(stmt);
+        while !self.cur_token_is(TokenType::RBRACE) && !self.cur_token_is(TokenType::EOF) {
+            match self.parse_statement() {
+                Ok(stmt) => block.push_statement(stmt),
+                Err(error) => {
+                    // 块内的错误在这里恢复，停在 `}` 上让块正常结束
+                    self.record_error(error)?;
+                    self.synchronize()?;
+                    if self.cur_token_is(TokenType::RBRACE) {
+                        break;
+                    }
+                }
+            }
             self.next_token()?;
         }
 
+        if !self.cur_token_is(TokenType::RBRACE) {
+            return Err(unexpected_token(RBRACE.to_string(), &self.current_token).into());
+        }
+
         Ok(block)
     }
 
@@ -443,18 +616,29 @@ impl<'a> Parser<'a> {
 
         lit.update_parameters(self.parse_function_parameters()?);
 
-        if self.expect_peek(TokenType::LBRACE).is_err() {
-            return Err(Error::CannotFindTokenType {
-                ty: "LBRACE".into(),
-            }
-            .into());
-        }
+        self.expect_peek(TokenType::LBRACE)?;
 
         lit.update_body(self.parse_block_statement()?);
 
         Ok(Expression::FunctionLiteral(lit))
     }
 
+    /// parse macro literals
+    #[tracing::instrument(name = "parse_macro_literal", skip(self), level = "debug")]
+    fn parse_macro_literal(&mut self) -> anyhow::Result<Expression> {
+        let mut lit = MacroLiteral::new(self.current_token.clone());
+
+        self.next_token()?; // skip `macro`
+
+        lit.update_parameters(self.parse_function_parameters()?);
+
+        self.expect_peek(TokenType::LBRACE)?;
+
+        lit.update_body(self.parse_block_statement()?);
+
+        Ok(Expression::MacroLiteral(lit))
+    }
+
     #[tracing::instrument(name = "parse_function_parameters", skip(self), level = "debug")]
     fn parse_function_parameters(&mut self) -> anyhow::Result<Vec<Identifier>> {
         let mut identifiers = Vec::<Identifier>::new();
@@ -490,13 +674,7 @@ impl<'a> Parser<'a> {
         }
         tracing::trace!("current_token {:?}", self.current_token);
 
-        if self.expect_peek(TokenType::RPAREN).is_err() {
-            tracing::trace!("expect_peek {}", self.peek_token.token_type());
-            return Err(Error::CannotFindTokenType {
-                ty: TokenType::RPAREN.to_string(),
-            }
-            .into());
-        }
+        self.expect_peek(TokenType::RPAREN)?;
 
         Ok(identifiers)
     }
@@ -518,12 +696,7 @@ impl<'a> Parser<'a> {
 
         exp.update_index(self.parse_expression(LOWEST)?);
 
-        if self.expect_peek(RBRACKET).is_err() {
-            return Err(Error::CannotFindTokenType {
-                ty: RBRACKET.to_string(),
-            }
-            .into());
-        }
+        self.expect_peek(RBRACKET)?;
 
         Ok(exp.into())
     }
@@ -555,12 +728,7 @@ impl<'a> Parser<'a> {
             args.push(self.parse_expression(LOWEST)?);
         }
 
-        if self.expect_peek(end.clone()).is_err() {
-            return Err(Error::CannotFindTokenType {
-                ty: end.to_string(),
-            }
-            .into());
-        }
+        self.expect_peek(end.clone())?;
 
         Ok(args)
     }
@@ -572,9 +740,7 @@ impl<'a> Parser<'a> {
         while !self.peek_token_is(RBRACE) {
             self.next_token()?;
             let key = self.parse_expression(LOWEST)?;
-            if self.expect_peek(COLON).is_err() {
-                return Err(Error::ExpectColonError.into());
-            }
+            self.expect_peek(COLON)?;
 
             self.next_token()?;
 
@@ -583,13 +749,13 @@ impl<'a> Parser<'a> {
             hash.pair_mut().insert(key, value);
 
             if !self.peek_token_is(RBRACE) && self.expect_peek(COMMA).is_err() {
-                return Err(Error::ExpectBraceAndCommaError.into());
+                return Err(
+                    unexpected_token(format!("{RBRACE}` or `{COMMA}"), &self.peek_token).into(),
+                );
             }
         }
 
-        if self.expect_peek(RBRACE).is_err() {
-            return Err(Error::ExpectRbraceError.into());
-        }
+        self.expect_peek(RBRACE)?;
 
         Ok(hash.into())
     }
@@ -610,14 +776,38 @@ impl<'a> Parser<'a> {
             self.next_token()?;
             Ok(())
         } else {
-            Err(Error::ExpectNextToken {
-                expected: t.to_string(),
-                got: self.peek_token.token_type().to_string(),
-            }
-            .into())
+            Err(unexpected_token(t.to_string(), &self.peek_token).into())
         }
     }
 
+    /// 语句结束：有 `;` 时吃掉它；没有 `;` 时，下一个词法单元必须是 `}`、EOF，
+    /// 或者位于新的一行，否则说明输入有误。以哈希字面量或函数字面量的 `}` 结尾的
+    /// 语句也一样，`let h = {"a": 1} h` 不能写在同一行
+    fn expect_statement_end(&mut self) -> anyhow::Result<()> {
+        if self.peek_token_is(TokenType::SEMICOLON) {
+            return self.next_token();
+        }
+
+        let on_new_line = self.peek_token.span().line > self.current_token.span().line;
+        if self.peek_token_is(TokenType::RBRACE)
+            || self.peek_token_is(TokenType::EOF)
+            || on_new_line
+        {
+            return Ok(());
+        }
+
+        Err(unexpected_token(TokenType::SEMICOLON.to_string(), &self.peek_token).into())
+    }
+
+    /// 以代码块结尾的 if、while 和 for：块的 `}` 已经结束了语句，
+    /// 后面可以直接写下一条语句，有 `;` 时吃掉它
+    fn expect_block_statement_end(&mut self) -> anyhow::Result<()> {
+        if self.peek_token_is(TokenType::SEMICOLON) {
+            return self.next_token();
+        }
+        Ok(())
+    }
+
     /// peekPrecedence 方法根据 p.peekToken 中的词法单元类型，返回所关联的优先
     /// 级。如果在 p.peekToken 中没有存储对应的优先级，则使用默认值 LOWEST，这是所
     /// 有运算符都可能具有的最低优先级。
@@ -640,3 +830,20 @@ impl<'a> Parser<'a> {
         self.infix_parse_fns.insert(token_type, infix_parse_fn);
     }
 }
+
+/// 遇到的词法单元不是期望的那个。ILLEGAL 单独报告成无法识别的字符，
+/// 比 "expected `;`, got `illegal`" 更能说明问题
+fn unexpected_token(expected: String, got: &Token) -> Error {
+    if got.token_type() == &TokenType::ILLEGAL {
+        return illegal_character(got);
+    }
+    Error::ExpectNextToken {
+        expected,
+        got: got.token_type().to_string(),
+        span: got.span(),
+    }
+}
+
+fn illegal_character(token: &Token) -> Error {
+    Error::IllegalCharacter(token.literal().to_string(), token.span())
+}
@@ -5,13 +5,21 @@ use std::collections::HashMap;
 #[repr(C)]
 pub enum OperatorPriority {
     LOWEST = 1,
-    EQUALS = 2,      // ==
-    LESSGREATER = 3, // < or >
-    SUM = 4,         // +
-    PRODUCT = 5,     // *
-    PREFIX = 6,      // -X or !x
-    CALL = 7,        // myFcuntion(x)
-    INDEX = 8,       // array[index]
+    ASSIGN = 2,      // x = y，右结合
+    OR = 3,          // ||
+    AND = 4,         // &&
+    EQUALS = 5,      // ==
+    LESSGREATER = 6, // < or > or <= or >=
+    BITOR = 7,       // |
+    BITXOR = 8,      // ^
+    BITAND = 9,      // &
+    SHIFT = 10,      // << or >>
+    SUM = 11,        // +
+    PRODUCT = 12,    // * or / or %
+    PREFIX = 13,     // -X or !x or ~x
+    POWER = 14,      // ** 比前缀运算符高：-2 ** 2 是 -(2 ** 2)
+    CALL = 15,       // myFcuntion(x)
+    INDEX = 16,      // array[index]
 }
 
 // precedences 就是优先级表，用于将词法单元类型与其优先级相关联。
@@ -20,14 +28,26 @@ lazy_static! {
     static ref PRECEDENCES: HashMap<TokenType, OperatorPriority> = {
         let mut m = HashMap::new();
         m.insert(TokenType::LPAREN, OperatorPriority::CALL);
+        m.insert(TokenType::ASSIGN, OperatorPriority::ASSIGN);
+        m.insert(TokenType::OR, OperatorPriority::OR);
+        m.insert(TokenType::AND, OperatorPriority::AND);
         m.insert(TokenType::EQ, OperatorPriority::EQUALS);
         m.insert(TokenType::NOTEQ, OperatorPriority::EQUALS);
         m.insert(TokenType::LT, OperatorPriority::LESSGREATER);
         m.insert(TokenType::GT, OperatorPriority::LESSGREATER);
+        m.insert(TokenType::LTEQ, OperatorPriority::LESSGREATER);
+        m.insert(TokenType::GTEQ, OperatorPriority::LESSGREATER);
+        m.insert(TokenType::PIPE, OperatorPriority::BITOR);
+        m.insert(TokenType::CARET, OperatorPriority::BITXOR);
+        m.insert(TokenType::AMPERSAND, OperatorPriority::BITAND);
+        m.insert(TokenType::SHL, OperatorPriority::SHIFT);
+        m.insert(TokenType::SHR, OperatorPriority::SHIFT);
         m.insert(TokenType::PLUS, OperatorPriority::SUM);
         m.insert(TokenType::MINUS, OperatorPriority::SUM);
         m.insert(TokenType::SLASH, OperatorPriority::PRODUCT);
         m.insert(TokenType::ASTERISK, OperatorPriority::PRODUCT);
+        m.insert(TokenType::PERCENT, OperatorPriority::PRODUCT);
+        m.insert(TokenType::POWER, OperatorPriority::POWER);
         m.insert(TokenType::LBRACKET, OperatorPriority::INDEX);
         m
     };
@@ -43,10 +63,19 @@ pub fn precedence(token_type: TokenType) -> OperatorPriority {
 #[test]
 fn test_operator_priority_type() {
     assert_eq!(OperatorPriority::LOWEST as u8, 1);
-    assert_eq!(OperatorPriority::EQUALS as u8, 2);
-    assert_eq!(OperatorPriority::LESSGREATER as u8, 3);
-    assert_eq!(OperatorPriority::SUM as u8, 4);
-    assert_eq!(OperatorPriority::PRODUCT as u8, 5);
-    assert_eq!(OperatorPriority::PREFIX as u8, 6);
-    assert_eq!(OperatorPriority::CALL as u8, 7);
+    assert_eq!(OperatorPriority::ASSIGN as u8, 2);
+    assert_eq!(OperatorPriority::OR as u8, 3);
+    assert_eq!(OperatorPriority::AND as u8, 4);
+    assert_eq!(OperatorPriority::EQUALS as u8, 5);
+    assert_eq!(OperatorPriority::LESSGREATER as u8, 6);
+    assert_eq!(OperatorPriority::BITOR as u8, 7);
+    assert_eq!(OperatorPriority::BITXOR as u8, 8);
+    assert_eq!(OperatorPriority::BITAND as u8, 9);
+    assert_eq!(OperatorPriority::SHIFT as u8, 10);
+    assert_eq!(OperatorPriority::SUM as u8, 11);
+    assert_eq!(OperatorPriority::PRODUCT as u8, 12);
+    assert_eq!(OperatorPriority::PREFIX as u8, 13);
+    assert_eq!(OperatorPriority::POWER as u8, 14);
+    assert_eq!(OperatorPriority::CALL as u8, 15);
+    assert_eq!(OperatorPriority::INDEX as u8, 16);
 }
@@ -1,4 +1,4 @@
-/// TODO have bug
+// TODO have bug
 
 static TRACE_LEVEL: global::Global<usize> = global::Global::new();
 
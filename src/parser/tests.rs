@@ -1,12 +1,15 @@
 use crate::ast::expression::array::ArrayLiteral;
 use crate::ast::expression::boolean::Boolean;
 use crate::ast::expression::call::Call;
+use crate::ast::expression::float::FloatLiteral;
 use crate::ast::expression::function::FunctionLiteral;
 use crate::ast::expression::hash::HashLiteral;
 use crate::ast::expression::if_expression::If;
 use crate::ast::expression::index::Index;
 use crate::ast::expression::infix::Infix;
 use crate::ast::expression::integer::IntegerLiteral;
+use crate::ast::expression::interpolated_string::InterpolatedString;
+use crate::ast::expression::macro_literal::MacroLiteral;
 use crate::ast::expression::prefix::Prefix;
 use crate::ast::expression::string::StringLiteral;
 use crate::ast::expression::Expression;
@@ -16,11 +19,13 @@ use crate::ast::statement::return_statement::ReturnStatement;
 use crate::ast::statement::Statement;
 use crate::ast::Identifier;
 use crate::ast::NodeInterface;
+use crate::error::Error;
 use crate::lexer::lexer;
 use crate::object::hash::Hash;
 use crate::object::string::StringObj;
 use crate::object::Object;
 use crate::parser::Parser;
+use crate::token::span::Span;
 use std::collections::{BTreeMap, HashMap};
 
 fn test_let_statements() -> anyhow::Result<()> {
@@ -30,7 +35,7 @@ fn test_let_statements() -> anyhow::Result<()> {
         expected_value: Interface,
     }
 
-    let tests = vec![
+    let tests = [
         LetStatementTest {
             input: "let x = 5;",
             expected_identifier: "x",
@@ -62,7 +67,7 @@ fn test_let_statements() -> anyhow::Result<()> {
             );
         }
 
-        let stmt = program.statements.get(0).unwrap();
+        let stmt = program.statements.first().unwrap();
 
         if !test_let_statement(stmt, tt.expected_identifier) {
             eprintln!("test let statement error");
@@ -134,7 +139,7 @@ fn test_return_statements() -> anyhow::Result<()> {
 
         let program = parser.parse_program()?;
 
-        let stmt = program.statements.get(0).unwrap();
+        let stmt = program.statements.first().unwrap();
         let return_stmt = ReturnStatement::try_from(stmt.clone()).unwrap();
 
         if return_stmt.token_literal() != "return" {
@@ -174,7 +179,7 @@ fn test_identifier_expression() -> anyhow::Result<()> {
     }
 
     let stmt: Option<Result<ExpressionStatement, anyhow::Error>> =
-        program.statements.get(0).map(|value| value.try_into());
+        program.statements.first().map(|value| value.try_into());
 
     println!("expression statement: {stmt:?}");
 
@@ -217,7 +222,7 @@ fn test_integer_literal_expression() -> anyhow::Result<()> {
     }
 
     let stmt: Option<Result<ExpressionStatement, anyhow::Error>> =
-        program.statements.get(0).map(|value| value.try_into());
+        program.statements.first().map(|value| value.try_into());
 
     println!("expression statement: {stmt:?}");
 
@@ -258,7 +263,7 @@ fn test_parsing_prefix_expression() -> anyhow::Result<()> {
         }
     }
 
-    let prefix_tests = vec![
+    let prefix_tests = [
         PrefixTest::new("!5;", "!", 5.into()),
         PrefixTest::new("-15;", "-", 15.into()),
         // PrefixTest::new("!foobar;", "!", 15),
@@ -283,7 +288,7 @@ fn test_parsing_prefix_expression() -> anyhow::Result<()> {
         }
 
         let stmt: Option<Result<ExpressionStatement, anyhow::Error>> =
-            program.statements.get(0).map(|value| value.try_into());
+            program.statements.first().map(|value| value.try_into());
         if stmt.is_none() {
             eprintln!("program statements[0] is not expression statement. got = {stmt:?}");
         }
@@ -374,7 +379,7 @@ fn test_parsing_infix_expression() -> anyhow::Result<()> {
         }
 
         let stmt: Option<Result<ExpressionStatement, anyhow::Error>> =
-            program.statements.get(0).map(|value| value.try_into());
+            program.statements.first().map(|value| value.try_into());
 
         if stmt.is_none() {
             eprintln!("program statements[0] is not ExpressionStatement. got = None");
@@ -654,7 +659,10 @@ fn test_if_expression() -> anyhow::Result<()> {
         );
     }
 
-    let stmt = program.statements.get(0).map(ExpressionStatement::try_from);
+    let stmt = program
+        .statements
+        .first()
+        .map(ExpressionStatement::try_from);
 
     if stmt.is_none() {
         eprintln!("program statements[0] is not ExpressionStatement. got = None");
@@ -687,7 +695,7 @@ fn test_if_expression() -> anyhow::Result<()> {
         .clone()
         .unwrap()
         .statements()
-        .get(0)
+        .first()
         .map(ExpressionStatement::try_from);
 
     if consequence.is_none() {
@@ -724,7 +732,10 @@ fn test_if_else_expression() -> anyhow::Result<()> {
         );
     }
 
-    let stmt = program.statements.get(0).map(ExpressionStatement::try_from);
+    let stmt = program
+        .statements
+        .first()
+        .map(ExpressionStatement::try_from);
 
     if stmt.is_none() {
         eprintln!("program statements[0] is not ExpressionStatement. got = None");
@@ -755,7 +766,7 @@ fn test_if_else_expression() -> anyhow::Result<()> {
         .clone()
         .unwrap()
         .statements()
-        .get(0)
+        .first()
         .map(ExpressionStatement::try_from);
 
     if alternative.is_none() {
@@ -786,7 +797,10 @@ fn test_function_literal_parsing() -> anyhow::Result<()> {
         );
     }
 
-    let stmt = program.statements.get(0).map(ExpressionStatement::try_from);
+    let stmt = program
+        .statements
+        .first()
+        .map(ExpressionStatement::try_from);
     if stmt.is_none() {
         eprintln!("program statements[0] is not  expression statement. got = None");
     }
@@ -820,7 +834,7 @@ fn test_function_literal_parsing() -> anyhow::Result<()> {
     let body_stmt = function
         .body()
         .statements()
-        .get(0)
+        .first()
         .map(ExpressionStatement::try_from);
     if body_stmt.is_none() {
         eprintln!("function body stmt is not ExpressionStatement. got = None");
@@ -837,6 +851,638 @@ fn test_function_literal_parsing() -> anyhow::Result<()> {
     Ok(())
 }
 
+fn test_macro_literal_parsing() -> anyhow::Result<()> {
+    let input = "macro(x, y) { x + y; }";
+
+    let lexer = lexer(input)?.1;
+
+    let mut parser = Parser::new(lexer)?;
+
+    let program = parser.parse_program()?;
+
+    if program.statements.len() != 1 {
+        return Err(anyhow::anyhow!(
+            "program statements does not contain 1 statements. got = {}",
+            program.statements.len()
+        ));
+    }
+
+    let stmt = ExpressionStatement::try_from(&program.statements[0])?;
+    let macro_literal = MacroLiteral::try_from(stmt.expression())?;
+
+    if macro_literal.parameters().len() != 2 {
+        return Err(anyhow::anyhow!(
+            "macro literal parameters wrong. want 2, got = {}",
+            macro_literal.parameters().len()
+        ));
+    }
+
+    if !Interface::from("x")
+        .test_literal_expression(macro_literal.parameters()[0].clone().into())?
+        || !Interface::from("y")
+            .test_literal_expression(macro_literal.parameters()[1].clone().into())?
+    {
+        return Err(anyhow::anyhow!("macro literal parameters are not x, y"));
+    }
+
+    if macro_literal.body().statements_len() != 1 {
+        return Err(anyhow::anyhow!(
+            "macro body statements wrong. want 1, got = {}",
+            macro_literal.body().statements_len()
+        ));
+    }
+
+    let body_stmt = ExpressionStatement::try_from(&macro_literal.body().statements()[0])?;
+    if !test_infix_expression(body_stmt.expression(), "x".into(), "+", "y".into())? {
+        return Err(anyhow::anyhow!("test infix expression error"));
+    }
+
+    Ok(())
+}
+
+fn test_node_spans() -> anyhow::Result<()> {
+    let input = "let x = 1;\nadd(x,\n  y * 2);";
+
+    let lexer = lexer(input)?.1;
+    let mut parser = Parser::new(lexer)?;
+    let program = parser.parse_program()?;
+
+    let let_stmt = LetStatement::try_from(&program.statements[0])?;
+    if let_stmt.span() != Span::new(0, 9, 1, 1) {
+        return Err(anyhow::anyhow!(
+            "let span wrong. got = {:?}",
+            let_stmt.span()
+        ));
+    }
+
+    let stmt = ExpressionStatement::try_from(&program.statements[1])?;
+    let call = Call::try_from(stmt.expression())?;
+    if call.span() != Span::new(11, 25, 2, 1) {
+        return Err(anyhow::anyhow!("call span wrong. got = {:?}", call.span()));
+    }
+
+    let infix = Infix::try_from(&call.arguments()[1])?;
+    if infix.span() != Span::new(20, 25, 3, 3) {
+        return Err(anyhow::anyhow!(
+            "infix span wrong. got = {:?}",
+            infix.span()
+        ));
+    }
+
+    Ok(())
+}
+
+fn test_parser_error_span() -> anyhow::Result<()> {
+    let input = "let x = 1;\nlet = 2;";
+
+    let lexer = lexer(input)?.1;
+    let mut parser = Parser::new(lexer)?;
+    if let Ok(program) = parser.parse_program() {
+        return Err(anyhow::anyhow!("expected parse error, got {program}"));
+    }
+
+    let error = &parser.errors()[0];
+    if error.span() != Some(Span::new(15, 16, 2, 5)) {
+        return Err(anyhow::anyhow!(
+            "error span wrong. got = {:?}",
+            error.span()
+        ));
+    }
+
+    Ok(())
+}
+
+fn test_parser_error_recovery() -> anyhow::Result<()> {
+    struct Test {
+        input: &'static str,
+        expected_lines: Vec<usize>,
+    }
+
+    let tests = vec![
+        Test {
+            input: "let = 1;\nlet y 2;\nlet z = 3;\nreturn );",
+            expected_lines: vec![1, 2, 4],
+        },
+        Test {
+            input: "let f = fn(x) {\n  let = x;\n  x +;\n};\nlet ok = 1;\nlet = 2;",
+            expected_lines: vec![2, 3, 6],
+        },
+        Test {
+            input: "if (x) { x",
+            expected_lines: vec![1],
+        },
+    ];
+
+    for tt in tests {
+        let lexer = lexer(tt.input)?.1;
+        let mut parser = Parser::new(lexer)?;
+        let error = match parser.parse_program() {
+            Ok(program) => return Err(anyhow::anyhow!("expected parse error, got {program}")),
+            Err(error) => error.downcast::<Error>()?,
+        };
+
+        let Error::ParserErrors(errors) = error else {
+            return Err(anyhow::anyhow!("expected ParserErrors, got {error:?}"));
+        };
+        if errors.len() != parser.errors().len() {
+            return Err(anyhow::anyhow!("errors() does not match returned errors"));
+        }
+
+        let lines = errors
+            .iter()
+            .map(|error| error.span().map(|span| span.line).unwrap_or_default())
+            .collect::<Vec<_>>();
+        if lines != tt.expected_lines {
+            return Err(anyhow::anyhow!(
+                "error lines wrong for `{}`. expected = {:?}, got = {:?} ({errors:?})",
+                tt.input,
+                tt.expected_lines,
+                lines
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn test_illegal_character_errors() -> anyhow::Result<()> {
+    let tests = vec![
+        ("let a = 5 @ 3;", vec![("@", Span::new(10, 11, 1, 11))]),
+        ("@foo;", vec![("@", Span::new(0, 1, 1, 1))]),
+        ("let b = [1, 2 # 3];", vec![("#", Span::new(14, 15, 1, 15))]),
+        (
+            "let c = 1 ? 2;\nlet d = 3;\nf(`x`);",
+            vec![
+                ("?", Span::new(10, 11, 1, 11)),
+                ("`", Span::new(28, 29, 3, 3)),
+            ],
+        ),
+    ];
+
+    for (input, expected) in tests {
+        let mut parser = Parser::new(lexer(input)?.1)?;
+        if let Ok(program) = parser.parse_program() {
+            return Err(anyhow::anyhow!(
+                "expected parse error for {input}, got {program}"
+            ));
+        }
+
+        let errors = parser
+            .errors()
+            .iter()
+            .map(|error| (error.to_string(), error.span()))
+            .collect::<Vec<_>>();
+        let expected = expected
+            .into_iter()
+            .map(|(ch, span)| (format!("illegal character `{ch}`"), Some(span)))
+            .collect::<Vec<_>>();
+        if errors != expected {
+            return Err(anyhow::anyhow!(
+                "errors wrong for {input}. expected = {expected:?}, got = {errors:?}"
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn test_optional_semicolons() -> anyhow::Result<()> {
+    struct Test {
+        input: &'static str,
+        expected: &'static str,
+    }
+
+    let tests = vec![
+        Test {
+            input: "let x = 5;",
+            expected: "let x = 5;",
+        },
+        Test {
+            input: "let x = 5",
+            expected: "let x = 5;",
+        },
+        Test {
+            input: "let x = 5\nlet y = x",
+            expected: "let x = 5;let y = x;",
+        },
+        Test {
+            input: "return 5;",
+            expected: "return 5;",
+        },
+        Test {
+            input: "return 5",
+            expected: "return 5;",
+        },
+        Test {
+            input: "return 5\nreturn 6",
+            expected: "return 5;return 6;",
+        },
+        Test {
+            input: "x + y;",
+            expected: "(x + y)",
+        },
+        Test {
+            input: "x + y",
+            expected: "(x + y)",
+        },
+        Test {
+            input: "x + y\nz",
+            expected: "(x + y)z",
+        },
+        Test {
+            input: "fn(x) { let y = x; return y; }",
+            expected: "fn(x)let y = x;return y;",
+        },
+        Test {
+            input: "fn(x) { let y = x\nreturn y }",
+            expected: "fn(x)let y = x;return y;",
+        },
+        Test {
+            input: "fn(x) { x }",
+            expected: "fn(x)x",
+        },
+        Test {
+            input: "if (x) { return 1 } return 2",
+            expected: "if x return 1;return 2;",
+        },
+        Test {
+            input: "let f = fn(x) { x }\nf(1)",
+            expected: "let f = fn(x)x;f(1)",
+        },
+        Test {
+            input: "let h = {\"a\": 1}\nh",
+            expected: "let h = {\"a\":1};h",
+        },
+    ];
+
+    for tt in tests {
+        let lexer = lexer(tt.input)?.1;
+        let mut parser = Parser::new(lexer)?;
+        let program = parser.parse_program()?;
+
+        if program.to_string() != tt.expected {
+            return Err(anyhow::anyhow!(
+                "program wrong for `{}`. expected = {}, got = {}",
+                tt.input,
+                tt.expected,
+                program
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn test_missing_semicolon_errors() -> anyhow::Result<()> {
+    let tests = [
+        "let x = 5 6",
+        "return 5 6;",
+        "x y",
+        "let x = 5 let y = 6;",
+        r#"let h = {"a": 1} h"#,
+        r#"return {"a": 1} h"#,
+        r#"{"a": 1} h"#,
+        "let f = fn(x) { x } f(1)",
+        "fn(x) { x } 1",
+    ];
+
+    for input in tests {
+        let lexer = lexer(input)?.1;
+        let mut parser = Parser::new(lexer)?;
+        if let Ok(program) = parser.parse_program() {
+            return Err(anyhow::anyhow!(
+                "expected parse error for `{input}`, got {program}"
+            ));
+        }
+
+        match parser.errors().first() {
+            Some(Error::ExpectNextToken { expected, .. }) if expected == ";" => {}
+            error => {
+                return Err(anyhow::anyhow!(
+                    "expected ExpectNextToken for `{input}`, got {error:?}"
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn test_assign_expression_parsing() -> anyhow::Result<()> {
+    let tests = vec![
+        ("x = 5;", "(x = 5)"),
+        ("x = y = 1;", "(x = (y = 1))"),
+        ("x = y = 5 + 1;", "(x = (y = (5 + 1)))"),
+        ("x = a || b;", "(x = (a || b))"),
+        ("a || (b = 1);", "(a || (b = 1))"),
+        ("x = x == 1;", "(x = (x == 1))"),
+        ("arr[1] = 2;", "((arr[1]) = 2)"),
+        (r#"h["k"][0] = f(1);"#, r#"(((h["k"])[0]) = f(1))"#),
+    ];
+
+    for (input, expected) in tests {
+        let lexer = lexer(input)?.1;
+        let mut parser = Parser::new(lexer)?;
+        let program = parser.parse_program()?;
+
+        if program.to_string() != expected {
+            return Err(anyhow::anyhow!(
+                "program wrong for `{input}`. expected = {expected}, got = {program}"
+            ));
+        }
+    }
+
+    for input in [
+        "1 = 2;",
+        "f() = 1;",
+        "x + y = 1;",
+        "a == b = c;",
+        "a || b = 1;",
+        "a && b = 1;",
+    ] {
+        let lexer = lexer(input)?.1;
+        let mut parser = Parser::new(lexer)?;
+        if let Ok(program) = parser.parse_program() {
+            return Err(anyhow::anyhow!(
+                "expected parse error for `{input}`, got {program}"
+            ));
+        }
+        if !matches!(
+            parser.errors().first(),
+            Some(Error::InvalidAssignmentTarget(..))
+        ) {
+            return Err(anyhow::anyhow!(
+                "expected InvalidAssignmentTarget for `{input}`, got {:?}",
+                parser.errors()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn test_loop_statement_parsing() -> anyhow::Result<()> {
+    let tests = vec![
+        (
+            "while (x < 3) { x = x + 1; }",
+            "while (x < 3) (x = (x + 1))",
+        ),
+        ("while (true) { break; };", "while true break;"),
+        (
+            "for (x in [1, 2]) { continue }",
+            "for x in [1, 2] continue;",
+        ),
+        (
+            "for (k in h) { puts(k) }\nbreak",
+            "for k in h puts(k)break;",
+        ),
+    ];
+
+    for (input, expected) in tests {
+        let lexer = lexer(input)?.1;
+        let mut parser = Parser::new(lexer)?;
+        let program = parser.parse_program()?;
+
+        if program.to_string() != expected {
+            return Err(anyhow::anyhow!(
+                "program wrong for `{input}`. expected = {expected}, got = {program}"
+            ));
+        }
+    }
+
+    for input in [
+        "while x { }",
+        "for (x of y) { }",
+        "for (1 in y) { }",
+        "break 1;",
+    ] {
+        let lexer = lexer(input)?.1;
+        let mut parser = Parser::new(lexer)?;
+        if let Ok(program) = parser.parse_program() {
+            return Err(anyhow::anyhow!(
+                "expected parse error for `{input}`, got {program}"
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn test_float_literal_expression() -> anyhow::Result<()> {
+    let tests = vec![
+        ("2.75;", 2.75, "2.75"),
+        ("1e-9;", 1e-9, "1e-9"),
+        ("2.5E3;", 2500.0, "2.5E3"),
+    ];
+
+    for (input, expected, literal) in tests {
+        let lexer = lexer(input)?.1;
+        let mut parser = Parser::new(lexer)?;
+        let program = parser.parse_program()?;
+
+        let stmt = ExpressionStatement::try_from(program.statements[0].clone())?;
+        let float = FloatLiteral::try_from(stmt.expression())?;
+        if float.value().value() != expected || float.to_string() != literal {
+            return Err(anyhow::anyhow!(
+                "float literal wrong. expected = {expected}, got = {float:?}"
+            ));
+        }
+    }
+
+    let lexer = lexer("-1.5 * 2")?.1;
+    let program = Parser::new(lexer)?.parse_program()?;
+    if program.to_string() != "((-1.5) * 2)" {
+        return Err(anyhow::anyhow!("float prefix wrong. got = {program}"));
+    }
+
+    Ok(())
+}
+
+fn test_string_literal_display_round_trip() -> anyhow::Result<()> {
+    let tests = vec![
+        (r#""say \"hi\"\n""#, r#""say \"hi\"\n""#),
+        (r#"r"C:\temp""#, r#""C:\\temp""#),
+        ("\"\"\"tab\there\nnext\"\"\"", r#""tab\there\nnext""#),
+        (r#""\u{7}😀""#, r#""\u{7}😀""#),
+    ];
+
+    for (input, expected) in tests {
+        let program = Parser::new(lexer(input)?.1)?.parse_program()?;
+        let printed = program.to_string();
+        if printed != expected {
+            return Err(anyhow::anyhow!(
+                "display wrong for {input}. expected = {expected}, got = {printed}"
+            ));
+        }
+
+        // 输出的字面量重新解析之后得到同样的字符串
+        let reparsed = Parser::new(lexer(&printed)?.1)?.parse_program()?;
+        let original = StringLiteral::try_from(
+            ExpressionStatement::try_from(program.statements[0].clone())?.expression(),
+        )?;
+        let round_trip = StringLiteral::try_from(
+            ExpressionStatement::try_from(reparsed.statements[0].clone())?.expression(),
+        )?;
+        if original.value() != round_trip.value() {
+            return Err(anyhow::anyhow!(
+                "round trip wrong for {input}. got = {:?}",
+                round_trip.value()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn test_interpolated_string_parsing() -> anyhow::Result<()> {
+    let tests = vec![
+        (
+            r#""hello ${name}!""#,
+            vec!["hello ", "!"],
+            r#""hello ${name}!""#,
+        ),
+        (
+            r#""${a + 1} and ${ f("}") }""#,
+            vec!["", " and ", ""],
+            r#""${(a + 1)} and ${f("}")}""#,
+        ),
+        (
+            r#""cost: \\${x} \${y}""#,
+            vec!["cost: \\", " ${y}"],
+            r#""cost: \\${x} \${y}""#,
+        ),
+    ];
+
+    for (input, strings, expected) in tests {
+        let program = Parser::new(lexer(input)?.1)?.parse_program()?;
+        let string = InterpolatedString::try_from(
+            ExpressionStatement::try_from(program.statements[0].clone())?.expression(),
+        )?;
+        if string.strings() != strings.as_slice() {
+            return Err(anyhow::anyhow!(
+                "strings wrong for {input}. expected = {strings:?}, got = {:?}",
+                string.strings()
+            ));
+        }
+        if string.expressions().len() + 1 != strings.len() {
+            return Err(anyhow::anyhow!(
+                "expressions wrong for {input}. got = {:?}",
+                string.expressions()
+            ));
+        }
+
+        let printed = program.to_string();
+        if printed != expected {
+            return Err(anyhow::anyhow!(
+                "display wrong for {input}. expected = {expected}, got = {printed}"
+            ));
+        }
+        // 输出的字面量重新解析之后得到同样的语法树
+        let reparsed = Parser::new(lexer(&printed)?.1)?.parse_program()?;
+        if reparsed.to_string() != printed {
+            return Err(anyhow::anyhow!(
+                "round trip wrong for {input}. got = {reparsed}"
+            ));
+        }
+    }
+
+    // 插值表达式不能为空，也必须用 `}` 结束
+    for input in [r#""a ${} b""#, r#""a ${x y} b""#] {
+        if Parser::new(lexer(input)?.1)?.parse_program().is_ok() {
+            return Err(anyhow::anyhow!("expected parse error for {input}"));
+        }
+    }
+
+    Ok(())
+}
+
+fn test_let_statement_doc_comments() -> anyhow::Result<()> {
+    let input = r#"
+/// The answer.
+/// Computed slowly.
+let answer = 42; // not a doc comment
+
+// plain comment
+let other = /* inline */ 1;
+
+/// documents the expression, not a let
+answer;
+"#;
+    let program = Parser::new(lexer(input)?.1)?.parse_program()?;
+    if program.statements.len() != 3 {
+        return Err(anyhow::anyhow!(
+            "program has wrong number of statements. got = {}",
+            program.statements.len()
+        ));
+    }
+
+    let expected = [Some("The answer.\nComputed slowly."), None];
+    for (statement, expected) in program.statements.iter().zip(expected) {
+        let statement = LetStatement::try_from(statement)?;
+        if statement.doc() != expected {
+            return Err(anyhow::anyhow!(
+                "doc comment wrong for {statement}. expected = {expected:?}, got = {:?}",
+                statement.doc()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn test_extended_operator_precedence() -> anyhow::Result<()> {
+    let tests = vec![
+        ("a <= b == c >= d", "((a <= b) == (c >= d))"),
+        ("a || b && c", "(a || (b && c))"),
+        ("a && b || c && d", "((a && b) || (c && d))"),
+        ("a == b && c != d", "((a == b) && (c != d))"),
+        ("a | b ^ c & d", "(a | (b ^ (c & d)))"),
+        ("a & b == c", "((a & b) == c)"),
+        ("a << 1 + b >> c", "((a << (1 + b)) >> c)"),
+        ("a + b % c * d", "(a + ((b % c) * d))"),
+        ("2 ** 3 ** 2", "(2 ** (3 ** 2))"),
+        ("a * b ** c", "(a * (b ** c))"),
+        ("-2 ** 2", "(-(2 ** 2))"),
+        ("2 ** -1", "(2 ** (-1))"),
+        ("~a & ~b", "((~a) & (~b))"),
+        ("x = a || b", "(x = (a || b))"),
+    ];
+
+    for (input, expected) in tests {
+        let program = Parser::new(lexer(input)?.1)?.parse_program()?;
+        if program.to_string() != expected {
+            return Err(anyhow::anyhow!(
+                "precedence wrong for {input}. expected = {expected}, got = {program}"
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// 解析完嵌套的子表达式之后，后面的语句要从正确的位置继续解析，
+/// 不能重新读到已经消费过的词法单元
+fn test_parser_position_after_sub_expressions() -> anyhow::Result<()> {
+    let tests = vec![
+        ("(1 + 2) * 3; 4", "((1 + 2) * 3)4"),
+        ("[1, 2][0] + f(3, 4); x", "(([1, 2][0]) + f(3, 4))x"),
+        ("let a = {\"k\": -1}; a", "let a = {\"k\":(-1)};a"),
+    ];
+
+    for (input, expected) in tests {
+        let lexer = lexer(input)?.1;
+        let mut parser = Parser::new(lexer)?;
+        let program = parser.parse_program()?;
+        if program.to_string() != expected {
+            return Err(anyhow::anyhow!(
+                "wrong program for {input}. expected = {expected}, got = {program}"
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 fn test_function_parameter_parsing() -> anyhow::Result<()> {
     struct Test<'a> {
         input: &'a str,
@@ -864,7 +1510,10 @@ fn test_function_parameter_parsing() -> anyhow::Result<()> {
 
         let program = parser.parse_program()?;
 
-        let stmt = program.statements.get(0).map(ExpressionStatement::try_from);
+        let stmt = program
+            .statements
+            .first()
+            .map(ExpressionStatement::try_from);
         let function = FunctionLiteral::try_from(stmt.unwrap().unwrap().expression())?;
 
         if function.parameters().len() != tt.expected_params.len() {
@@ -896,7 +1545,10 @@ fn test_call_expression_parsing() -> anyhow::Result<()> {
         );
     }
 
-    let stmt = program.statements.get(0).map(ExpressionStatement::try_from);
+    let stmt = program
+        .statements
+        .first()
+        .map(ExpressionStatement::try_from);
 
     if stmt.is_none() {
         eprintln!("stmt is not ExpressionStatement. got = None");
@@ -950,7 +1602,10 @@ fn test_call_expression_parameter_parsing() -> anyhow::Result<()> {
         let mut parser = Parser::new(lexer)?;
         let program = parser.parse_program()?;
 
-        let stmt = program.statements.get(0).map(ExpressionStatement::try_from);
+        let stmt = program
+            .statements
+            .first()
+            .map(ExpressionStatement::try_from);
         let exp = Call::try_from(stmt.unwrap().unwrap().expression())?;
 
         if !test_identifier(exp.function().clone(), tt.expected_ident)? {
@@ -987,7 +1642,10 @@ fn test_string_literal_expression() -> anyhow::Result<()> {
 
     let program = parser.parse_program()?;
 
-    let stmt = program.statements.get(0).map(ExpressionStatement::try_from);
+    let stmt = program
+        .statements
+        .first()
+        .map(ExpressionStatement::try_from);
 
     let literal = StringLiteral::try_from(stmt.unwrap().unwrap().expression())?;
 
@@ -1005,7 +1663,10 @@ fn test_parsing_array_literals() -> anyhow::Result<()> {
     let mut parser = Parser::new(lexer)?;
     let program = parser.parse_program()?;
 
-    let stmt = program.statements.get(0).map(ExpressionStatement::try_from);
+    let stmt = program
+        .statements
+        .first()
+        .map(ExpressionStatement::try_from);
 
     let array = ArrayLiteral::try_from(stmt.unwrap().unwrap().expression())?;
 
@@ -1027,7 +1688,10 @@ fn test_parsing_index_expression() -> anyhow::Result<()> {
     let program = parser.parse_program()?;
     println!("test_test_parsing_index_expression: program = {program:#?}");
 
-    let stmt = program.statements.get(0).map(ExpressionStatement::try_from);
+    let stmt = program
+        .statements
+        .first()
+        .map(ExpressionStatement::try_from);
 
     println!("test_test_parsing_index_expression: Stmt = {stmt:#?}");
     let index_exp = Index::try_from(stmt.unwrap().unwrap().expression())?;
@@ -1049,7 +1713,10 @@ fn test_parsing_hash_literals_string_keys() -> anyhow::Result<()> {
     let lexer = lexer(input)?.1;
     let mut parser = Parser::new(lexer)?;
     let program = parser.parse_program()?;
-    let stmt = program.statements.get(0).map(ExpressionStatement::try_from);
+    let stmt = program
+        .statements
+        .first()
+        .map(ExpressionStatement::try_from);
 
     let hash = HashLiteral::try_from(stmt.unwrap().unwrap().expression())?;
 
@@ -1080,7 +1747,10 @@ fn test_parsing_empty_hash_literal() -> anyhow::Result<()> {
     let lexer = lexer(input)?.1;
     let mut parser = Parser::new(lexer)?;
     let program = parser.parse_program()?;
-    let stmt = program.statements.get(0).map(ExpressionStatement::try_from);
+    let stmt = program
+        .statements
+        .first()
+        .map(ExpressionStatement::try_from);
     let hash = HashLiteral::try_from(stmt.unwrap().unwrap().expression())?;
 
     if !hash.pair().is_empty() {
@@ -1096,7 +1766,10 @@ fn test_parsing_hash_literals_with_expressions() -> anyhow::Result<()> {
     let lexer = lexer(input)?.1;
     let mut parser = Parser::new(lexer)?;
     let program = parser.parse_program()?;
-    let stmt = program.statements.get(0).map(ExpressionStatement::try_from);
+    let stmt = program
+        .statements
+        .first()
+        .map(ExpressionStatement::try_from);
 
     let hash = HashLiteral::try_from(stmt.unwrap().unwrap().expression())?;
 
@@ -1241,6 +1914,111 @@ fn test_test_function_literal_parsing() {
     println!("test_function_literal_parsing: ret = {ret:?}");
 }
 
+#[test]
+fn test_test_node_spans() {
+    let ret = test_node_spans();
+    println!("test_node_spans: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_parser_error_span() {
+    let ret = test_parser_error_span();
+    println!("test_parser_error_span: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_parser_error_recovery() {
+    let ret = test_parser_error_recovery();
+    println!("test_parser_error_recovery: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_optional_semicolons() {
+    let ret = test_optional_semicolons();
+    println!("test_optional_semicolons: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_illegal_character_errors() {
+    let ret = test_illegal_character_errors();
+    println!("test_illegal_character_errors: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_missing_semicolon_errors() {
+    let ret = test_missing_semicolon_errors();
+    println!("test_missing_semicolon_errors: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_assign_expression_parsing() {
+    let ret = test_assign_expression_parsing();
+    println!("test_assign_expression_parsing: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_loop_statement_parsing() {
+    let ret = test_loop_statement_parsing();
+    println!("test_loop_statement_parsing: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_float_literal_expression() {
+    let ret = test_float_literal_expression();
+    println!("test_float_literal_expression: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_string_literal_display_round_trip() {
+    let ret = test_string_literal_display_round_trip();
+    println!("test_string_literal_display_round_trip: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_interpolated_string_parsing() {
+    let ret = test_interpolated_string_parsing();
+    println!("test_interpolated_string_parsing: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_let_statement_doc_comments() {
+    let ret = test_let_statement_doc_comments();
+    println!("test_let_statement_doc_comments: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_extended_operator_precedence() {
+    let ret = test_extended_operator_precedence();
+    println!("test_extended_operator_precedence: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_parser_position_after_sub_expressions() {
+    let ret = test_parser_position_after_sub_expressions();
+    println!("test_parser_position_after_sub_expressions: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
+#[test]
+fn test_test_macro_literal_parsing() {
+    let ret = test_macro_literal_parsing();
+    println!("test_macro_literal_parsing: ret = {ret:?}");
+    assert!(ret.is_ok());
+}
+
 #[test]
 fn test_test_function_parameter_parsing() {
     let ret = test_function_parameter_parsing();
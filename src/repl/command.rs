@@ -0,0 +1,175 @@
+use crate::diagnostics::{self, ColorMode, Diagnostic, Renderer};
+use crate::error::Error;
+use crate::lexer::lexer;
+use crate::object::environment::Environment;
+use crate::object::ObjectInterface;
+use crate::parser::Parser;
+use crate::runner;
+use std::io::Write;
+
+/// 命令中代码参数的诊断信息使用的文件名
+const COMMAND_FILE_NAME: &str = "<command>";
+
+/// 所有命令的用法和说明，`:help` 按这个顺序输出
+const COMMANDS: &[(&str, &str)] = &[
+    (":tokens <code>", "show the tokens produced by the lexer"),
+    (
+        ":ast <code>",
+        "show the parsed program with every expression parenthesized",
+    ),
+    (":env", "show the bindings in the current environment"),
+    (
+        ":type <expr>",
+        "evaluate an expression and show the type of its value",
+    ),
+    (":load <file>", "evaluate a file into the current session"),
+    (":reset", "clear all bindings and macros, keeping `args`"),
+    (":quit", "exit the REPL"),
+    (":help", "show this help"),
+];
+
+/// REPL 中以 `:` 开头的命令
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Tokens(String),
+    Ast(String),
+    Env,
+    Type(String),
+    Load(String),
+    Reset,
+    Quit,
+    Help,
+}
+
+/// 命令执行之后 REPL 是否继续
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    Continue,
+    Quit,
+}
+
+impl Command {
+    /// 解析一行输入，不是以 `:` 开头时返回 None
+    pub fn parse(input: &str) -> anyhow::Result<Option<Self>> {
+        let Some(input) = input.trim().strip_prefix(':') else {
+            return Ok(None);
+        };
+        let (name, argument) = match input.split_once(char::is_whitespace) {
+            Some((name, argument)) => (name, argument.trim()),
+            None => (input, ""),
+        };
+        let argument = || match argument {
+            "" => Err(Error::MissingCommandArgument(format!(":{name}"))),
+            argument => Ok(argument.to_string()),
+        };
+
+        let command = match name {
+            "tokens" => Self::Tokens(argument()?),
+            "ast" => Self::Ast(argument()?),
+            "env" => Self::Env,
+            "type" => Self::Type(argument()?),
+            "load" => Self::Load(argument()?),
+            "reset" => Self::Reset,
+            "quit" | "q" => Self::Quit,
+            "help" | "h" | "?" => Self::Help,
+            _ => return Err(Error::UnknownCommand(format!(":{name}")).into()),
+        };
+        Ok(Some(command))
+    }
+
+    /// 执行命令，结果输出到 `std_out`，错误的诊断信息输出到 `std_err`；
+    /// `args` 是启动 REPL 时的参数，`:reset` 之后重新绑定。只有 `exit()` 会作为错误返回
+    pub fn execute(
+        &self,
+        env: &mut Environment,
+        macro_env: &mut Environment,
+        args: &[String],
+        std_out: &mut impl Write,
+        std_err: &mut impl Write,
+        color: ColorMode,
+    ) -> anyhow::Result<Flow> {
+        let renderer = |source| Renderer::new(COMMAND_FILE_NAME, source).with_color(color);
+
+        match self {
+            Self::Tokens(code) => match lexer(code) {
+                Ok((_, tokens)) => {
+                    for token in tokens {
+                        let _ = writeln!(std_out, "{token:?}");
+                    }
+                }
+                Err(error) => report(std_err, &renderer(code), error)?,
+            },
+            Self::Ast(code) => {
+                let program = lexer(code)
+                    .and_then(|(_, tokens)| Parser::new(tokens))
+                    .and_then(|mut parser| parser.parse_program());
+                match program {
+                    // 每条语句一行，表达式带上括号以显示运算符的结合方式
+                    Ok(program) => {
+                        for statement in &program.statements {
+                            let _ = writeln!(std_out, "{statement}");
+                        }
+                    }
+                    Err(error) => report(std_err, &renderer(code), error)?,
+                }
+            }
+            Self::Env => {
+                let _ = write!(std_out, "{env}");
+            }
+            Self::Type(code) => match runner::eval_source(code, env, macro_env) {
+                Ok(value) => {
+                    let _ = writeln!(std_out, "{}", value.object_type());
+                }
+                Err(error) => report(std_err, &renderer(code), error)?,
+            },
+            Self::Load(path) => match std::fs::read_to_string(path) {
+                Ok(source) => {
+                    if let Err(error) = runner::eval_source(&source, env, macro_env) {
+                        let renderer = Renderer::new(path, &source).with_color(color);
+                        report(std_err, &renderer, error)?;
+                    }
+                }
+                Err(error) => {
+                    let diagnostic = Diagnostic::new(format!("cannot read `{path}`: {error}"));
+                    let report = Renderer::new(path, "")
+                        .with_color(color)
+                        .render(&diagnostic);
+                    let _ = std_err.write_all(report.as_bytes());
+                }
+            },
+            Self::Reset => {
+                env.clear();
+                macro_env.clear();
+                runner::bind_args(env, args);
+            }
+            Self::Quit => return Ok(Flow::Quit),
+            Self::Help => {
+                let width = COMMANDS.iter().map(|(usage, _)| usage.len()).max();
+                for (usage, description) in COMMANDS {
+                    let _ = writeln!(
+                        std_out,
+                        "{usage:<width$}  {description}",
+                        width = width.unwrap_or(0)
+                    );
+                }
+            }
+        }
+        let _ = std_out.flush();
+        let _ = std_err.flush();
+        Ok(Flow::Continue)
+    }
+}
+
+/// 输出错误的诊断信息，`exit()` 原样返回交给调用方结束 REPL
+fn report(
+    std_err: &mut impl Write,
+    renderer: &Renderer,
+    error: anyhow::Error,
+) -> anyhow::Result<()> {
+    if runner::exit_code(&error).is_some() {
+        return Err(error);
+    }
+    let report = renderer.render_all(&diagnostics::collect(&error));
+    let _ = std_err.write_all(report.as_bytes());
+    Ok(())
+}
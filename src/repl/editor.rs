@@ -0,0 +1,147 @@
+use crate::evaluator::builtins::BUILTINS;
+use crate::object::environment::Environment;
+use crate::token::token_type::KEYWORDS;
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::history::DefaultHistory;
+use rustyline::{Context, Editor, Helper, Highlighter, Hinter, Validator};
+use std::io;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use unicode_xid::UnicodeXID;
+
+/// 历史记录文件所在的目录名，位于用户的数据目录下
+const HISTORY_DIR_NAME: &str = "star-kirby-lang";
+const HISTORY_FILE_NAME: &str = "history.txt";
+
+/// REPL 按行读取输入的方式
+pub trait LineReader {
+    /// 显示提示符并读取一行，返回的内容带有换行符；输入结束时返回 None
+    fn read_line(&mut self, prompt: &str, std_out: &mut dyn Write) -> io::Result<Option<String>>;
+
+    /// 记录一段完整的输入
+    fn add_history(&mut self, _entry: &str) {}
+}
+
+/// 标准输入不是终端时直接按行读取，没有行编辑和历史记录
+impl<R: BufRead> LineReader for R {
+    fn read_line(&mut self, prompt: &str, std_out: &mut dyn Write) -> io::Result<Option<String>> {
+        std_out.write_all(prompt.as_bytes())?;
+        std_out.flush()?;
+
+        let mut line = String::new();
+        match BufRead::read_line(self, &mut line)? {
+            0 => Ok(None),
+            _ => Ok(Some(line)),
+        }
+    }
+}
+
+/// 终端上的行编辑器：支持方向键编辑、Ctrl-R 反向搜索历史和 Tab 补全，
+/// 历史记录保存在用户数据目录下的文件中
+pub struct LineEditor {
+    editor: Editor<MonkeyHelper, DefaultHistory>,
+    history: Option<PathBuf>,
+}
+
+impl LineEditor {
+    /// `env` 是 REPL 的顶层环境，其中的名字会参与补全
+    pub fn new(env: Environment) -> rustyline::Result<Self> {
+        let mut editor = Editor::new()?;
+        editor.set_helper(Some(MonkeyHelper { env }));
+
+        let history = history_path();
+        if let Some(path) = &history {
+            // 第一次运行时还没有历史记录文件
+            let _ = editor.load_history(path);
+        }
+        Ok(Self { editor, history })
+    }
+}
+
+impl LineReader for LineEditor {
+    fn read_line(&mut self, prompt: &str, _std_out: &mut dyn Write) -> io::Result<Option<String>> {
+        match self.editor.readline(prompt) {
+            Ok(line) => Ok(Some(line + "\n")),
+            Err(ReadlineError::Eof) => Ok(None),
+            Err(ReadlineError::Interrupted) => Err(io::ErrorKind::Interrupted.into()),
+            Err(ReadlineError::Io(error)) => Err(error),
+            Err(error) => Err(io::Error::other(error)),
+        }
+    }
+
+    fn add_history(&mut self, entry: &str) {
+        let entry = entry.trim_end();
+        if entry.trim().is_empty() || !matches!(self.editor.add_history_entry(entry), Ok(true)) {
+            return;
+        }
+        // 每次都写回文件，exit() 直接结束进程时也不会丢失历史记录
+        if let Some(path) = &self.history {
+            if let Some(dir) = path.parent() {
+                let _ = std::fs::create_dir_all(dir);
+            }
+            let _ = self.editor.save_history(path);
+        }
+    }
+}
+
+fn history_path() -> Option<PathBuf> {
+    Some(
+        dirs::data_dir()?
+            .join(HISTORY_DIR_NAME)
+            .join(HISTORY_FILE_NAME),
+    )
+}
+
+/// 补全光标前的标识符：关键字、内置函数以及 REPL 环境中已经绑定的名字
+#[derive(Helper, Highlighter, Hinter, Validator)]
+struct MonkeyHelper {
+    env: Environment,
+}
+
+impl Completer for MonkeyHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let start = word_start(line, pos);
+        Ok((start, completions(&self.env, &line[start..pos])))
+    }
+}
+
+/// 光标前标识符的起始位置，光标前不是标识符时就是光标的位置
+fn word_start(line: &str, pos: usize) -> usize {
+    let mut start = pos;
+    for (index, ch) in line[..pos].char_indices().rev() {
+        if !(ch.is_xid_continue() || ch == '_') {
+            break;
+        }
+        start = index;
+    }
+    // 数字开头的不是标识符，比如 `12` 不应该补全
+    match line[start..pos].chars().next() {
+        Some(ch) if ch.is_xid_start() || ch == '_' => start,
+        _ => pos,
+    }
+}
+
+/// 以 `prefix` 开头的关键字、内置函数和 `env` 中的名字，按字典序排列
+pub fn completions(env: &Environment, prefix: &str) -> Vec<String> {
+    if prefix.is_empty() {
+        return vec![];
+    }
+    let mut candidates: Vec<String> = KEYWORDS
+        .keys()
+        .map(|keyword| keyword.to_string())
+        .chain(BUILTINS.keys().cloned())
+        .chain(env.names())
+        .filter(|name| name.starts_with(prefix))
+        .collect();
+    candidates.sort();
+    candidates.dedup();
+    candidates
+}
@@ -0,0 +1,71 @@
+use crate::error::Error;
+use crate::lexer::lexer;
+use crate::token::token_type::TokenType;
+
+/// 判断 REPL 的输入是否还没有写完，需要继续读取下一行：
+/// `{ ( [` 没有闭合、字符串或块注释没有结束，或者以运算符结尾。
+///
+/// 其他的词法错误不算没写完，交给后面的求值流程报告。
+pub fn is_incomplete(source: &str) -> bool {
+    let tokens = match lexer(source) {
+        Ok((_, tokens)) => tokens,
+        Err(error) => {
+            return matches!(
+                error.downcast_ref::<Error>(),
+                Some(Error::UnterminatedString(_) | Error::UnterminatedComment(_))
+            )
+        }
+    };
+
+    let mut depth: usize = 0;
+    for token in &tokens {
+        match token.token_type() {
+            TokenType::LPAREN | TokenType::LBRACE | TokenType::LBRACKET => depth += 1,
+            // 多出来的右括号是语法错误，不需要继续等待输入
+            TokenType::RPAREN | TokenType::RBRACE | TokenType::RBRACKET => {
+                depth = depth.saturating_sub(1)
+            }
+            _ => {}
+        }
+    }
+    if depth > 0 {
+        return true;
+    }
+
+    tokens
+        .iter()
+        .rev()
+        .find(|token| token.token_type() != &TokenType::EOF)
+        .is_some_and(|token| expects_operand(token.token_type()))
+}
+
+/// 以这些词法单元结尾时，后面一定还有一个操作数
+fn expects_operand(token_type: &TokenType) -> bool {
+    matches!(
+        token_type,
+        TokenType::ASSIGN
+            | TokenType::PLUS
+            | TokenType::MINUS
+            | TokenType::BANG
+            | TokenType::ASTERISK
+            | TokenType::SLASH
+            | TokenType::PERCENT
+            | TokenType::POWER
+            | TokenType::AMPERSAND
+            | TokenType::PIPE
+            | TokenType::CARET
+            | TokenType::TILDE
+            | TokenType::SHL
+            | TokenType::SHR
+            | TokenType::AND
+            | TokenType::OR
+            | TokenType::LT
+            | TokenType::GT
+            | TokenType::LTEQ
+            | TokenType::GTEQ
+            | TokenType::EQ
+            | TokenType::NOTEQ
+            | TokenType::COMMA
+            | TokenType::COLON
+    )
+}
@@ -1,14 +1,36 @@
 use crate::ast::Node;
+use crate::diagnostics::{self, ColorMode, Renderer};
+use crate::error::Error;
 use crate::lexer::lexer;
+use crate::object::built_in_function::set_output;
 use crate::object::environment::Environment;
 use crate::object::ObjectInterface;
 use crate::parser::Parser;
+use crate::runner;
+use command::{Command, Flow};
+use editor::{LineEditor, LineReader};
+use std::cell::RefCell;
 use std::io;
 use std::io::BufRead;
+use std::io::IsTerminal;
 use std::io::Write;
+use std::rc::Rc;
+
+pub mod command;
+pub mod editor;
+pub mod input;
+
+#[cfg(test)]
+mod tests;
 
 const PROMPT: &str = ">> ";
 
+/// 输入还没有写完时的续行提示符
+const CONTINUATION_PROMPT: &str = ".. ";
+
+/// REPL 中诊断信息使用的文件名
+const REPL_FILE_NAME: &str = "<repl>";
+
 const MONKEY_FACE: &str = r#"            __,__
 .--.  .-"     "-.  .--.
 / .. \/  .-. .-.  \/ .. \
@@ -22,22 +44,137 @@ const MONKEY_FACE: &str = r#"            __,__
            '-----'
 "#;
 
-pub fn start(std_in: io::Stdin, mut std_out: io::Stdout) -> anyhow::Result<()> {
-    let mut std_buffer_reader = io::BufReader::new(std_in);
-    let mut env = Environment::new();
+/// 从 `std_in` 逐行读取输入，结果输出到 `std_out`，错误的诊断信息输出到 `std_err`。
+/// 程序中 `puts` 的输出也会立即写到 `std_out` 中。
+/// 输入结束或者执行 `:quit` 时返回；程序调用 `exit()` 时返回对应的错误
+pub fn start<R: BufRead, W: Write + 'static, E: Write>(
+    mut std_in: R,
+    std_out: W,
+    mut std_err: E,
+) -> anyhow::Result<()> {
+    let env = runner::new_environment(&[]);
+    let mut std_out = SharedWriter::new(std_out);
+    let _output = OutputGuard(set_output(Some(Box::new(std_out.clone()))));
+    run(
+        &mut std_in,
+        env,
+        &[],
+        &mut std_out,
+        &mut std_err,
+        ColorMode::Plain,
+    )
+}
 
-    loop {
-        let _ = std_out.write_all(PROMPT.as_ref());
-        let _ = std_out.flush();
+/// REPL 和程序中的 `puts` 共用的输出
+struct SharedWriter<W>(Rc<RefCell<W>>);
+
+impl<W> SharedWriter<W> {
+    fn new(writer: W) -> Self {
+        Self(Rc::new(RefCell::new(writer)))
+    }
+}
+
+impl<W> Clone for SharedWriter<W> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<W: Write> Write for SharedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.borrow_mut().flush()
+    }
+}
+
+/// 离开作用域时把 `puts` 的输出恢复为设置之前的输出
+struct OutputGuard(Option<Box<dyn Write>>);
+
+impl Drop for OutputGuard {
+    fn drop(&mut self) {
+        set_output(self.0.take());
+    }
+}
+
+/// 使用进程的标准输入输出启动 REPL：标准输入是终端时使用行编辑器，否则按行读取。
+/// `args` 绑定为环境中的 `args`
+pub fn start_stdio(args: &[String]) -> anyhow::Result<()> {
+    let env = runner::new_environment(args);
+    let mut std_out = io::stdout();
+    let mut std_err = io::stderr();
+    let color = ColorMode::detect(&std_err);
+
+    let editor = match io::stdin().is_terminal() {
+        true => LineEditor::new(env.clone()).ok(),
+        false => None,
+    };
+    match editor {
+        Some(mut editor) => run(&mut editor, env, args, &mut std_out, &mut std_err, color),
+        None => run(
+            &mut io::stdin().lock(),
+            env,
+            args,
+            &mut std_out,
+            &mut std_err,
+            color,
+        ),
+    }
+}
+
+fn run(
+    reader: &mut impl LineReader,
+    mut env: Environment,
+    args: &[String],
+    std_out: &mut impl Write,
+    std_err: &mut impl Write,
+    color: ColorMode,
+) -> anyhow::Result<()> {
+    let mut macro_env = Environment::new();
+    // 会话中所有输入拼接成的源码。每次输入的位置都换算到这份源码中，
+    // 之前输入中定义的函数在运行时出错也能指向正确的代码
+    let mut session = String::new();
+
+    // 输入结束（比如 Ctrl-D）时换行后退出，不再输出提示符
+    while let Some(buffer_reader) = read_input(reader, std_out) {
+        reader.add_history(&buffer_reader);
 
-        let mut buffer_reader = String::new();
-        let _line = std_buffer_reader.read_line(&mut buffer_reader);
+        match Command::parse(&buffer_reader) {
+            Ok(Some(command)) => {
+                match command.execute(&mut env, &mut macro_env, args, std_out, std_err, color)? {
+                    Flow::Continue => continue,
+                    Flow::Quit => return Ok(()),
+                }
+            }
+            Ok(None) => {}
+            Err(error) => {
+                let renderer = Renderer::new(REPL_FILE_NAME, &buffer_reader).with_color(color);
+                print_error(std_err, renderer.render_all(&diagnostics::collect(&error)));
+                continue;
+            }
+        }
+
+        let (offset, lines) = (session.len(), session.matches('\n').count());
+        session.push_str(&buffer_reader);
+        if !session.ends_with('\n') {
+            session.push('\n');
+        }
+        let renderer = Renderer::new(REPL_FILE_NAME, &session).with_color(color);
 
         let lexer = lexer(buffer_reader.as_str());
         let lexer = match lexer {
-            Ok((_, value)) => value,
+            Ok((_, value)) => value
+                .into_iter()
+                .map(|token| {
+                    let span = token.span().offset(offset, lines);
+                    token.with_span(span)
+                })
+                .collect(),
             Err(error) => {
-                print_parser_error(io::stdout(), error.to_string());
+                let error = offset_error(error, offset, lines);
+                print_parser_error(std_err, renderer.render_all(&diagnostics::collect(&error)));
                 continue;
             }
         };
@@ -46,16 +183,25 @@ pub fn start(std_in: io::Stdin, mut std_out: io::Stdout) -> anyhow::Result<()> {
         let mut parser = match parser {
             Ok(value) => value,
             Err(error) => {
-                print_parser_error(io::stdout(), error.to_string());
+                print_parser_error(std_err, renderer.render_all(&diagnostics::collect(&error)));
                 continue;
             }
         };
 
         let program = parser.parse_program();
-        let program = match program {
+        let mut program = match program {
             Ok(value) => value,
             Err(error) => {
-                print_parser_error(io::stdout(), error.to_string());
+                print_parser_error(std_err, renderer.render_all(&diagnostics::collect(&error)));
+                continue;
+            }
+        };
+
+        program.define_macros(&mut macro_env);
+        let program = match program.expand_macros(&mut macro_env) {
+            Ok(value) => value,
+            Err(error) => {
+                print_error(std_err, renderer.render_all(&diagnostics::collect(&error)));
                 continue;
             }
         };
@@ -68,17 +214,62 @@ pub fn start(std_in: io::Stdin, mut std_out: io::Stdout) -> anyhow::Result<()> {
                 let _ = std_out.write_all(format!("{value}\n").as_ref());
                 let _ = std_out.flush();
             }
+            // exit() 结束整个 REPL，退出码由调用方处理
+            Err(error) if runner::exit_code(&error).is_some() => return Err(error),
             Err(error) => {
-                tracing::error!("{error}");
+                print_error(std_err, renderer.render_all(&diagnostics::collect(&error)));
                 continue;
             }
         }
     }
+
+    let _ = std_out.write_all(b"\n");
+    let _ = std_out.flush();
+    Ok(())
+}
+
+/// 读取一段完整的输入，输入没有写完时显示续行提示符继续读取下一行。
+/// Ctrl-C 放弃已经输入的内容重新开始；输入结束并且没有读到任何内容时返回 None
+fn read_input(reader: &mut impl LineReader, std_out: &mut impl Write) -> Option<String> {
+    let mut buffer = String::new();
+    let mut prompt = PROMPT;
+    loop {
+        match reader.read_line(prompt, std_out) {
+            Ok(Some(line)) => buffer.push_str(&line),
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {
+                buffer.clear();
+                prompt = PROMPT;
+                continue;
+            }
+            // 输入已经结束时不再等待，直接把已有的内容交给求值流程
+            Ok(None) | Err(_) if buffer.is_empty() => return None,
+            Ok(None) | Err(_) => return Some(buffer),
+        }
+        // `:` 开头的命令总是只占一行
+        if buffer.trim_start().starts_with(':') || !input::is_incomplete(&buffer) {
+            return Some(buffer);
+        }
+        prompt = CONTINUATION_PROMPT;
+    }
+}
+
+/// 把词法错误中的位置换算到会话源码中
+fn offset_error(error: anyhow::Error, offset: usize, lines: usize) -> anyhow::Error {
+    match error.downcast::<Error>() {
+        Ok(error) => error.offset_span(offset, lines).into(),
+        Err(error) => error,
+    }
+}
+
+fn print_error(std_err: &mut impl Write, report: String) {
+    let _ = std_err.write_all(report.as_bytes());
+    let _ = std_err.flush();
 }
 
-fn print_parser_error(mut std_out: io::Stdout, error: String) {
-    let _ret = std_out.write_all(MONKEY_FACE.as_bytes());
-    let _ret = std_out.write_all("Woops! We ran into some monkey business here!\n".as_bytes());
-    let _ret = std_out.write_all(" parser errors:\n".as_bytes());
-    let _ret = std_out.write_all(format!("\t{error}\n").as_bytes());
+fn print_parser_error(std_err: &mut impl Write, error: String) {
+    let _ret = std_err.write_all(MONKEY_FACE.as_bytes());
+    let _ret = std_err.write_all("Woops! We ran into some monkey business here!\n".as_bytes());
+    let _ret = std_err.write_all(" parser errors:\n".as_bytes());
+    let _ret = std_err.write_all(error.as_bytes());
+    let _ret = std_err.flush();
 }
@@ -0,0 +1,364 @@
+use crate::diagnostics::ColorMode;
+use crate::object::environment::Environment;
+use crate::object::integer::Integer;
+use crate::object::ObjectInterface;
+use crate::repl::command::{Command, Flow};
+use crate::repl::editor::completions;
+use crate::repl::editor::LineReader;
+use crate::repl::input::is_incomplete;
+use crate::repl::{read_input, start, SharedWriter};
+use crate::runner;
+use std::io;
+use std::io::{Cursor, Write};
+
+#[test]
+fn test_is_incomplete() {
+    let tests = vec![
+        ("let add = fn(x, y) {\n", true),
+        ("let add = fn(x, y) {\n x + y\n", true),
+        ("let add = fn(x, y) {\n x + y\n};\n", false),
+        ("add(1,\n", true),
+        ("[1, 2\n", true),
+        ("{\"a\": \n", true),
+        ("\"hello\n", true),
+        ("\"\"\"multi\nline\n", true),
+        ("\"a ${ 1 +\n", true),
+        ("/* comment\n", true),
+        ("1 +\n", true),
+        ("let x =\n", true),
+        ("true &&\n", true),
+        ("1 + 2\n", false),
+        ("\"a ${ {\"k\": 1}[\"k\"] } b\"\n", false),
+        ("", false),
+        ("\n", false),
+        // 语法错误不再等待输入，交给解析器报告
+        ("1 + 2)\n", false),
+        ("}\n", false),
+        ("\"\\q\"\n", false),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(is_incomplete(input), expected, "input: {input:?}");
+    }
+}
+
+#[test]
+fn test_read_input_continuation() {
+    let mut reader = Cursor::new("let f = fn(x) {\n  x * 2\n};\nf(2)\n");
+    let mut output = vec![];
+
+    let input = read_input(&mut reader, &mut output);
+    assert_eq!(input.as_deref(), Some("let f = fn(x) {\n  x * 2\n};\n"));
+    assert_eq!(String::from_utf8(output).unwrap(), ">> .. .. ");
+
+    let mut output = vec![];
+    let input = read_input(&mut reader, &mut output);
+    assert_eq!(input.as_deref(), Some("f(2)\n"));
+    assert_eq!(String::from_utf8(output).unwrap(), ">> ");
+}
+
+#[test]
+fn test_read_input_eof_while_incomplete() {
+    let mut reader = Cursor::new("let x = [1,\n");
+    let mut output = vec![];
+
+    let input = read_input(&mut reader, &mut output);
+    assert_eq!(input.as_deref(), Some("let x = [1,\n"));
+    assert_eq!(String::from_utf8(output).unwrap(), ">> .. ");
+
+    let mut output = vec![];
+    assert_eq!(read_input(&mut reader, &mut output), None);
+    assert_eq!(String::from_utf8(output).unwrap(), ">> ");
+}
+
+/// 按顺序返回预先准备好的行，`None` 模拟 Ctrl-C
+struct ScriptedReader(Vec<Option<&'static str>>);
+
+impl LineReader for ScriptedReader {
+    fn read_line(&mut self, prompt: &str, std_out: &mut dyn Write) -> io::Result<Option<String>> {
+        std_out.write_all(prompt.as_bytes())?;
+        if self.0.is_empty() {
+            return Ok(None);
+        }
+        match self.0.remove(0) {
+            Some(line) => Ok(Some(format!("{line}\n"))),
+            None => Err(io::ErrorKind::Interrupted.into()),
+        }
+    }
+}
+
+#[test]
+fn test_read_input_interrupted() {
+    let mut reader = ScriptedReader(vec![Some("let f = fn() {"), None, Some("1 + 1")]);
+    let mut output = vec![];
+
+    let input = read_input(&mut reader, &mut output);
+    assert_eq!(input.as_deref(), Some("1 + 1\n"));
+    assert_eq!(String::from_utf8(output).unwrap(), ">> .. >> ");
+}
+
+fn run_repl(input: &str) -> (anyhow::Result<()>, String, String) {
+    let output = SharedWriter::new(vec![]);
+    let mut errors = vec![];
+    let ret = start(Cursor::new(input), output.clone(), &mut errors);
+    let output = output.0.borrow().clone();
+    (
+        ret,
+        String::from_utf8(output).unwrap(),
+        String::from_utf8(errors).unwrap(),
+    )
+}
+
+#[test]
+fn test_start_until_eof() {
+    let (ret, output, errors) = run_repl("let x = 1;\nx + 1\nx + y\nlet = ;\n");
+    assert!(ret.is_ok());
+    assert_eq!(output, ">> null\n>> 2\n>> >> >> \n");
+    assert!(errors.contains("identifier not found: `y`"), "{errors}");
+    assert!(errors.contains("parser errors"), "{errors}");
+    assert!(!errors.contains("\"level\""), "{errors}");
+
+    let (ret, output, errors) = run_repl("");
+    assert!(ret.is_ok());
+    assert_eq!((output.as_str(), errors.as_str()), (">> \n", ""));
+}
+
+#[test]
+fn test_start_puts_to_std_out() {
+    let (ret, output, errors) = run_repl("puts(1, \"a\")\nlet f = fn() { puts(2); y }; f()\n");
+    assert!(ret.is_ok());
+    assert_eq!(output, ">> 1\na\nnull\n>> 2\n>> \n");
+    assert!(errors.contains("identifier not found: `y`"), "{errors}");
+}
+
+#[test]
+fn test_start_puts_in_command() {
+    let (ret, output, errors) = run_repl(":type puts(1)\n");
+    assert!(ret.is_ok());
+    assert_eq!(
+        (output.as_str(), errors.as_str()),
+        (">> 1\nNULL\n>> \n", "")
+    );
+}
+
+/// 最多写入 `limit` 个字节，之后写入失败
+struct LimitedWriter {
+    written: Vec<u8>,
+    limit: usize,
+}
+
+impl Write for LimitedWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written.len() + buf.len() > self.limit {
+            return Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"));
+        }
+        self.written.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_start_streams_puts() {
+    // puts 的输出立即写出，所以死循环会在输出关闭时因为写入失败而停止
+    let output = SharedWriter::new(LimitedWriter {
+        written: vec![],
+        limit: 9,
+    });
+    let mut errors = vec![];
+    let ret = start(
+        Cursor::new("while (true) { puts(1) }\n"),
+        output.clone(),
+        &mut errors,
+    );
+    assert!(ret.is_ok());
+    assert_eq!(output.0.borrow().written, b">> 1\n1\n1\n");
+    assert!(String::from_utf8(errors).unwrap().contains("closed"));
+}
+
+#[test]
+fn test_start_error_in_earlier_input() {
+    let (ret, output, errors) =
+        run_repl("let f = fn(x) { let yyyyyyyyyyyyyy = 1; x + true };\nf(1)\nlet s = \"é${\n");
+    assert!(ret.is_ok());
+    assert_eq!(output, ">> null\n>> >> .. >> \n");
+    assert!(errors.contains(" --> <repl>:1:41\n"), "{errors}");
+    assert!(errors.contains("x + true"), "{errors}");
+    assert!(errors.contains(" --> <repl>:3:9\n"), "{errors}");
+}
+
+#[test]
+fn test_start_quit_and_exit() {
+    let (ret, output, _) = run_repl(":quit\n1\n");
+    assert!(ret.is_ok());
+    assert_eq!(output, ">> ");
+
+    let (ret, output, _) = run_repl("exit(4)\n1\n");
+    assert_eq!(runner::exit_code(&ret.unwrap_err()), Some(4));
+    assert_eq!(output, ">> ");
+}
+
+#[test]
+fn test_completions() {
+    let outer = Environment::new();
+    outer.store("lenient".into(), Integer::from(1).into());
+    let env = Environment::new_enclosed_environment(outer);
+    env.store("retry".into(), Integer::from(2).into());
+    env.store("lenient".into(), Integer::from(3).into());
+
+    let tests = vec![
+        ("le", vec!["len", "lenient", "let"]),
+        ("re", vec!["rest", "retry", "return"]),
+        ("wh", vec!["while"]),
+        ("fi", vec!["first"]),
+        ("zzz", vec![]),
+        ("", vec![]),
+    ];
+
+    for (prefix, expected) in tests {
+        assert_eq!(completions(&env, prefix), expected, "prefix: {prefix:?}");
+    }
+}
+
+#[test]
+fn test_parse_command() {
+    let tests = vec![
+        ("1 + 2\n", None),
+        (":env\n", Some(Command::Env)),
+        (
+            "  :tokens  let x = 1;  \n",
+            Some(Command::Tokens("let x = 1;".into())),
+        ),
+        (
+            ":ast fn(x) { x }\n",
+            Some(Command::Ast("fn(x) { x }".into())),
+        ),
+        (":type [1, 2]\n", Some(Command::Type("[1, 2]".into()))),
+        (
+            ":load lib.monkey\n",
+            Some(Command::Load("lib.monkey".into())),
+        ),
+        (":reset\n", Some(Command::Reset)),
+        (":quit\n", Some(Command::Quit)),
+        (":q\n", Some(Command::Quit)),
+        (":help\n", Some(Command::Help)),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(Command::parse(input).unwrap(), expected, "input: {input:?}");
+    }
+
+    let errors = vec![
+        (":foo\n", "unknown command `:foo`"),
+        (":type\n", "command `:type` expects an argument"),
+        (":load   \n", "command `:load` expects an argument"),
+    ];
+
+    for (input, expected) in errors {
+        let error = Command::parse(input).unwrap_err();
+        assert_eq!(error.to_string(), expected, "input: {input:?}");
+    }
+}
+
+/// 执行命令，返回标准输出和标准错误合在一起的内容
+fn execute(command: &str, env: &mut Environment) -> (Flow, String) {
+    let command = Command::parse(command).unwrap().unwrap();
+    let mut output = vec![];
+    let mut errors = vec![];
+    let flow = command
+        .execute(
+            env,
+            &mut Environment::new(),
+            &[],
+            &mut output,
+            &mut errors,
+            ColorMode::Plain,
+        )
+        .unwrap();
+    output.extend(errors);
+    (flow, String::from_utf8(output).unwrap())
+}
+
+#[test]
+fn test_execute_command() {
+    let mut env = runner::new_environment(&[]);
+
+    let (_, output) = execute(":tokens let x", &mut env);
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].contains("LET"), "{output}");
+    assert!(lines[1].contains("\"x\""), "{output}");
+    assert!(lines[2].contains("EOF"), "{output}");
+    assert!(!output.contains("doc"), "{output}");
+
+    let (_, output) = execute(":ast 1 + 2 * 3; let x = -a[0]", &mut env);
+    assert_eq!(output, "(1 + (2 * 3))\nlet x = (-(a[0]));\n");
+
+    let (_, output) = execute(":type let x = 1; x", &mut env);
+    assert_eq!(output, "INTEGER\n");
+    let (_, output) = execute(":type \"a\" + \"b\"", &mut env);
+    assert_eq!(output, "STRING\n");
+    let (_, output) = execute(":type len", &mut env);
+    assert_eq!(output, "BUILTIN\n");
+
+    let (_, output) = execute(":env", &mut env);
+    assert!(output.contains("x: 1"), "{output}");
+
+    let (_, output) = execute(":type y", &mut env);
+    assert!(output.contains("identifier not found"), "{output}");
+
+    let (flow, output) = execute(":reset", &mut env);
+    assert_eq!((flow, output.as_str()), (Flow::Continue, ""));
+    assert_eq!(env.names(), vec!["args".to_string()]);
+
+    let (flow, _) = execute(":quit", &mut env);
+    assert_eq!(flow, Flow::Quit);
+
+    let (_, output) = execute(":help", &mut env);
+    assert_eq!(output.lines().count(), 8);
+}
+
+#[test]
+fn test_reset_keeps_args() {
+    let args = ["a".to_string(), "b".to_string()];
+    let mut env = runner::new_environment(&args);
+    let mut macro_env = Environment::new();
+    runner::eval_source("args = []; let x = 1;", &mut env, &mut macro_env).unwrap();
+
+    let flow = Command::Reset
+        .execute(
+            &mut env,
+            &mut macro_env,
+            &args,
+            &mut vec![],
+            &mut vec![],
+            ColorMode::Plain,
+        )
+        .unwrap();
+    assert_eq!(flow, Flow::Continue);
+    assert_eq!(env.names(), vec!["args".to_string()]);
+    assert_eq!(env.get("args".into()).unwrap().inspect(), "[a,b]");
+}
+
+#[test]
+fn test_execute_load_command() {
+    let path = std::env::temp_dir().join(format!("repl-load-{}.monkey", std::process::id()));
+    std::fs::write(&path, "let double = fn(x) { x * 2 };").unwrap();
+
+    let mut env = runner::new_environment(&[]);
+    let (_, output) = execute(&format!(":load {}", path.display()), &mut env);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(output, "");
+
+    let (_, output) = execute(":type double(2)", &mut env);
+    assert_eq!(output, "INTEGER\n");
+
+    let (_, output) = execute(":load /no/such/file.monkey", &mut env);
+    assert!(
+        output.contains("cannot read `/no/such/file.monkey`"),
+        "{output}"
+    );
+}
@@ -0,0 +1,117 @@
+use crate::ast::Node;
+use crate::diagnostics::{self, ColorMode, Diagnostic, Renderer};
+use crate::error::Error;
+use crate::lexer::lexer;
+use crate::object::array::Array;
+use crate::object::environment::Environment;
+use crate::object::string::StringObj;
+use crate::object::{Object, ObjectInterface};
+use crate::parser::Parser;
+use std::io;
+use std::io::Write;
+
+/// 程序正常结束时的退出码
+pub const EXIT_SUCCESS: i32 = 0;
+/// 读取源码失败，或者程序有语法错误、运行时错误时的退出码
+pub const EXIT_FAILURE: i32 = 1;
+
+/// 对一段完整的源码依次做词法分析、语法分析、宏展开和求值
+pub fn eval_source(
+    source: &str,
+    env: &mut Environment,
+    macro_env: &mut Environment,
+) -> anyhow::Result<Object> {
+    let (_, tokens) = lexer(source)?;
+    let mut parser = Parser::new(tokens)?;
+    let mut program = parser.parse_program()?;
+
+    program.define_macros(macro_env);
+    let program = program.expand_macros(macro_env)?;
+
+    Node::from(program).eval(env)
+}
+
+/// 顶层环境，脚本参数以字符串数组 `args` 的形式提供给程序
+pub fn new_environment(args: &[String]) -> Environment {
+    let env = Environment::new();
+    bind_args(&env, args);
+    env
+}
+
+/// 把脚本参数绑定为环境中的 `args`
+pub fn bind_args(env: &Environment, args: &[String]) {
+    let args = args
+        .iter()
+        .map(|arg| StringObj::new(arg.clone()).into())
+        .collect();
+    env.store("args".into(), Array::new(args).into());
+}
+
+/// 程序调用了 `exit(code)` 时返回对应的退出码
+pub fn exit_code(error: &anyhow::Error) -> Option<i32> {
+    match error.downcast_ref::<Error>() {
+        Some(Error::Exit(code)) => Some(*code),
+        _ => None,
+    }
+}
+
+/// 运行一段完整的程序，出错时把诊断信息输出到标准错误，返回进程的退出码。
+/// `name` 是诊断信息中显示的文件名；`print_result` 为 true 时输出程序最后的值（null 除外）
+pub fn run_source(name: &str, source: &str, args: &[String], print_result: bool) -> i32 {
+    let mut env = new_environment(args);
+    let mut macro_env = Environment::new();
+    match eval_source(source, &mut env, &mut macro_env) {
+        Ok(value) => {
+            if print_result && !matches!(value, Object::Null(_)) {
+                println!("{}", value.inspect());
+            }
+            EXIT_SUCCESS
+        }
+        Err(error) => match exit_code(&error) {
+            Some(code) => code,
+            None => {
+                let mut stderr = io::stderr();
+                let report = Renderer::new(name, source)
+                    .with_color(ColorMode::detect(&stderr))
+                    .render_all(&diagnostics::collect(&error));
+                let _ = stderr.write_all(report.as_bytes());
+                EXIT_FAILURE
+            }
+        },
+    }
+}
+
+/// 运行脚本文件，返回进程的退出码
+pub fn run_file(path: &str, args: &[String]) -> i32 {
+    match std::fs::read_to_string(path) {
+        Ok(source) => run_source(path, &source, args, false),
+        Err(error) => {
+            let diagnostic = Diagnostic::new(format!("cannot read `{path}`: {error}"));
+            report_error(path, &diagnostic);
+            EXIT_FAILURE
+        }
+    }
+}
+
+/// 从标准输入读取整个程序并运行，返回进程的退出码
+pub fn run_stdin(args: &[String]) -> i32 {
+    const STDIN_FILE_NAME: &str = "<stdin>";
+
+    let mut source = String::new();
+    match io::Read::read_to_string(&mut io::stdin(), &mut source) {
+        Ok(_) => run_source(STDIN_FILE_NAME, &source, args, false),
+        Err(error) => {
+            let diagnostic = Diagnostic::new(format!("cannot read standard input: {error}"));
+            report_error(STDIN_FILE_NAME, &diagnostic);
+            EXIT_FAILURE
+        }
+    }
+}
+
+fn report_error(name: &str, diagnostic: &Diagnostic) {
+    let mut stderr = io::stderr();
+    let report = Renderer::new(name, "")
+        .with_color(ColorMode::detect(&stderr))
+        .render(diagnostic);
+    let _ = stderr.write_all(report.as_bytes());
+}
@@ -1,13 +1,61 @@
+pub mod span;
 pub mod token_type;
+use crate::token::span::Span;
 use crate::token::token_type::TokenType;
-use std::fmt::{Display, Formatter};
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
 
-#[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Clone)]
 pub struct Token {
     /// identifier for token type
     token_type: TokenType,
     /// identifier for token value
     literal: String,
+    /// position in source code, not part of token equality
+    span: Span,
+    /// doc comments (`///`) right before a `let` or `macro` token, not part of token equality
+    doc: Option<String>,
+}
+
+// 比较、哈希时忽略 span：同一个词素出现在不同位置仍然是同一个词法单元，
+// 这样 AST 节点（以及 HashLiteral 的 key）的相等性只取决于结构
+impl PartialEq for Token {
+    fn eq(&self, other: &Self) -> bool {
+        self.token_type == other.token_type && self.literal == other.literal
+    }
+}
+
+impl Eq for Token {}
+
+impl PartialOrd for Token {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Token {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.token_type, &self.literal).cmp(&(&other.token_type, &other.literal))
+    }
+}
+
+impl Hash for Token {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.token_type.hash(state);
+        self.literal.hash(state);
+    }
+}
+
+// `:tokens` 用 Debug 输出词法单元，文档注释只是附带的信息，不在这里输出
+impl Debug for Token {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Token")
+            .field("token_type", &self.token_type)
+            .field("literal", &self.literal)
+            .field("span", &self.span)
+            .finish()
+    }
 }
 
 impl Display for Token {
@@ -25,6 +73,8 @@ impl Token {
         Self {
             token_type,
             literal: ch.into(),
+            span: Span::default(),
+            doc: None,
         }
     }
 
@@ -32,6 +82,8 @@ impl Token {
         Self {
             token_type,
             literal,
+            span: Span::default(),
+            doc: None,
         }
     }
 
@@ -43,6 +95,24 @@ impl Token {
         &self.literal
     }
 
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+
+    pub fn doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
+
+    pub fn with_doc(mut self, doc: String) -> Self {
+        self.doc = Some(doc);
+        self
+    }
+
     pub fn token_type_mut(&mut self) -> &mut TokenType {
         &mut self.token_type
     }
@@ -0,0 +1,70 @@
+use std::fmt::{Display, Formatter};
+
+/// 词法单元在源码中的位置
+/// start/end 是字节偏移（左闭右开），line/column 从 1 开始，表示 start 所在的行列
+#[derive(Debug, Default, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize, line: usize, column: usize) -> Self {
+        Self {
+            start,
+            end,
+            line,
+            column,
+        }
+    }
+
+    /// 没有来源的位置，比如测试或宏展开中手工构造的词法单元
+    pub fn is_unknown(&self) -> bool {
+        self.line == 0
+    }
+
+    /// 整体后移 `bytes` 个字节、`lines` 行，用于把一段单独分析的源码
+    /// 拼接到已有源码的后面（比如 REPL 会话中的每一次输入）
+    pub fn offset(self, bytes: usize, lines: usize) -> Span {
+        if self.is_unknown() {
+            return self;
+        }
+        Span {
+            start: self.start + bytes,
+            end: self.end + bytes,
+            line: self.line + lines,
+            column: self.column,
+        }
+    }
+
+    /// 合并两个位置，得到覆盖两者的最小范围
+    pub fn merge(self, other: Span) -> Span {
+        if self.is_unknown() {
+            return other;
+        }
+        if other.is_unknown() {
+            return self;
+        }
+
+        let first = if self.start <= other.start {
+            self
+        } else {
+            other
+        };
+
+        Span {
+            start: first.start,
+            end: self.end.max(other.end),
+            line: first.line,
+            column: first.column,
+        }
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
@@ -13,8 +13,16 @@ pub enum TokenType {
     IDENT,
     /// 12345
     INT,
+    /// 3.14, 1e-9
+    FLOAT,
     /// "String"
     STRING,
+    /// "hello ${ 插值字符串第一个 `${` 之前的部分
+    STRINGSTART,
+    /// } and ${ 两个插值表达式之间的部分
+    STRINGMIDDLE,
+    /// } world" 最后一个插值表达式之后的部分
+    STRINGEND,
 
     /// =
     ASSIGN,
@@ -28,11 +36,37 @@ pub enum TokenType {
     ASTERISK,
     /// /
     SLASH,
+    /// %
+    PERCENT,
+    /// **
+    POWER,
+
+    /// &
+    AMPERSAND,
+    /// |
+    PIPE,
+    /// ^
+    CARET,
+    /// ~
+    TILDE,
+    /// <<
+    SHL,
+    /// >>
+    SHR,
+
+    /// &&
+    AND,
+    /// ||
+    OR,
 
     /// <
     LT,
     /// >
     GT,
+    /// <=
+    LTEQ,
+    /// >=
+    GTEQ,
 
     /// ==
     EQ,
@@ -73,6 +107,18 @@ pub enum TokenType {
     ELSE,
     /// return
     RETURN,
+    /// macro
+    MACRO,
+    /// while
+    WHILE,
+    /// for
+    FOR,
+    /// in
+    IN,
+    /// break
+    BREAK,
+    /// continue
+    CONTINUE,
 }
 
 impl Display for TokenType {
@@ -82,15 +128,31 @@ impl Display for TokenType {
             Self::EOF => write!(f, "eof"),
             Self::IDENT => write!(f, "ident"),
             Self::INT => write!(f, "int"),
+            Self::FLOAT => write!(f, "float"),
             Self::STRING => write!(f, "String"),
+            Self::STRINGSTART => write!(f, "string start"),
+            Self::STRINGMIDDLE => write!(f, "string middle"),
+            Self::STRINGEND => write!(f, "string end"),
             Self::ASSIGN => write!(f, "="),
             Self::PLUS => write!(f, "+"),
             Self::MINUS => write!(f, "-"),
             Self::BANG => write!(f, "!"),
             Self::ASTERISK => write!(f, "*"),
             Self::SLASH => write!(f, "/"),
+            Self::PERCENT => write!(f, "%"),
+            Self::POWER => write!(f, "**"),
+            Self::AMPERSAND => write!(f, "&"),
+            Self::PIPE => write!(f, "|"),
+            Self::CARET => write!(f, "^"),
+            Self::TILDE => write!(f, "~"),
+            Self::SHL => write!(f, "<<"),
+            Self::SHR => write!(f, ">>"),
+            Self::AND => write!(f, "&&"),
+            Self::OR => write!(f, "||"),
             Self::LT => write!(f, "<"),
             Self::GT => write!(f, ">"),
+            Self::LTEQ => write!(f, "<="),
+            Self::GTEQ => write!(f, ">="),
             Self::EQ => write!(f, "=="),
             Self::NOTEQ => write!(f, "!="),
             Self::COMMA => write!(f, ","),
@@ -109,12 +171,18 @@ impl Display for TokenType {
             Self::IF => write!(f, "if"),
             Self::ELSE => write!(f, "else"),
             Self::RETURN => write!(f, "return"),
+            Self::MACRO => write!(f, "macro"),
+            Self::WHILE => write!(f, "while"),
+            Self::FOR => write!(f, "for"),
+            Self::IN => write!(f, "in"),
+            Self::BREAK => write!(f, "break"),
+            Self::CONTINUE => write!(f, "continue"),
         }
     }
 }
 
 lazy_static! {
-    static ref KEYWORDS: HashMap<&'static str, TokenType> = {
+    pub static ref KEYWORDS: HashMap<&'static str, TokenType> = {
         let mut m = HashMap::new();
         m.insert("fn", TokenType::FUNCTION);
         m.insert("let", TokenType::LET);
@@ -123,6 +191,12 @@ lazy_static! {
         m.insert("if", TokenType::IF);
         m.insert("else", TokenType::ELSE);
         m.insert("return", TokenType::RETURN);
+        m.insert("macro", TokenType::MACRO);
+        m.insert("while", TokenType::WHILE);
+        m.insert("for", TokenType::FOR);
+        m.insert("in", TokenType::IN);
+        m.insert("break", TokenType::BREAK);
+        m.insert("continue", TokenType::CONTINUE);
         m
     };
 }
@@ -153,6 +227,11 @@ pub fn lookup_char(ch: char) -> TokenType {
         '-' => TokenType::MINUS,
         '!' => TokenType::BANG,
         '=' => TokenType::ASSIGN,
+        '%' => TokenType::PERCENT,
+        '&' => TokenType::AMPERSAND,
+        '|' => TokenType::PIPE,
+        '^' => TokenType::CARET,
+        '~' => TokenType::TILDE,
         _ => TokenType::ILLEGAL,
     }
 }